@@ -0,0 +1,222 @@
+use chrono::{DateTime, Duration, DurationRound, TimeZone, Utc};
+
+use crate::cache::Cache;
+use crate::pricing::PricingTable;
+use crate::transcript::{self, UsageEntry};
+
+/// Length of a Claude usage-limit billing block.
+pub const BLOCK_HOURS: i64 = 5;
+
+/// How far back transcripts are scanned when reconstructing blocks.
+/// Blocks chain from one another, so a single 5-hour window is not enough
+/// to know where the current one started.
+const LOOKBACK_HOURS: i64 = 24;
+
+/// A 5-hour billing block reconstructed from transcript timestamps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    /// First message of the block, floored to the hour
+    pub start: DateTime<Utc>,
+    /// `start` + 5 hours — when the usage limit resets
+    pub end: DateTime<Utc>,
+    /// Timestamp of the most recent message in the block
+    pub last_activity: DateTime<Utc>,
+    /// All tokens consumed in the block, including cache reads and writes
+    pub tokens: u64,
+    /// Cost in USD of the block at API prices
+    pub cost_usd: f64,
+    /// Number of assistant messages in the block
+    pub messages: usize,
+}
+
+impl Block {
+    fn open(entry: &UsageEntry) -> Self {
+        let start = entry
+            .timestamp
+            .duration_trunc(Duration::hours(1))
+            .unwrap_or(entry.timestamp);
+        Self {
+            start,
+            end: start + Duration::hours(BLOCK_HOURS),
+            last_activity: entry.timestamp,
+            tokens: 0,
+            cost_usd: 0.0,
+            messages: 0,
+        }
+    }
+
+    fn push(&mut self, entry: &UsageEntry, pricing: &PricingTable) {
+        self.last_activity = entry.timestamp;
+        self.tokens += entry.usage.total();
+        self.cost_usd += pricing.cost(&entry.model, &entry.usage).unwrap_or(0.0);
+        self.messages += 1;
+    }
+
+    /// Whether the block is still running at `now`.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        now < self.end
+    }
+}
+
+/// Group time-sorted usage entries into billing blocks.
+///
+/// A block starts at the first message after the previous block expired,
+/// floored to the hour, and lasts 5 hours. A gap of more than 5 hours since
+/// the last message also starts a new block.
+pub fn identify_blocks(entries: &[UsageEntry], pricing: &PricingTable) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+
+    for entry in entries {
+        let needs_new = match blocks.last() {
+            Some(b) => {
+                entry.timestamp >= b.end
+                    || entry.timestamp - b.last_activity > Duration::hours(BLOCK_HOURS)
+            }
+            None => true,
+        };
+        if needs_new {
+            blocks.push(Block::open(entry));
+        }
+        if let Some(b) = blocks.last_mut() {
+            b.push(entry, pricing);
+        }
+    }
+
+    blocks
+}
+
+/// Reconstruct the currently active billing block from local transcripts.
+/// Cached for 30 seconds since scanning transcripts is comparatively slow.
+pub fn active_block(pricing: &PricingTable) -> Option<Block> {
+    let cache = Cache::new();
+    let now = Utc::now();
+
+    if let Some(cached) = cache.get("active_block", 30) {
+        // An empty entry records "no active block" without rescanning
+        return parse_cached_block(&cached).filter(|b| b.is_active(now));
+    }
+
+    let entries = transcript::recent_entries(now - Duration::hours(LOOKBACK_HOURS));
+    let block = identify_blocks(&entries, pricing)
+        .pop()
+        .filter(|b| b.is_active(now));
+
+    cache.set(
+        "active_block",
+        &block.as_ref().map(serialize_block).unwrap_or_default(),
+    );
+
+    block
+}
+
+/// Serialize a block for the cache.
+/// Format: start|end|last_activity|tokens|cost|messages (timestamps in epoch seconds)
+fn serialize_block(b: &Block) -> String {
+    format!(
+        "{}|{}|{}|{}|{}|{}",
+        b.start.timestamp(),
+        b.end.timestamp(),
+        b.last_activity.timestamp(),
+        b.tokens,
+        b.cost_usd,
+        b.messages
+    )
+}
+
+fn parse_cached_block(cached: &str) -> Option<Block> {
+    let parts: Vec<&str> = cached.trim().split('|').collect();
+    if parts.len() != 6 {
+        return None;
+    }
+
+    let ts = |s: &str| s.parse::<i64>().ok().and_then(|t| Utc.timestamp_opt(t, 0).single());
+
+    Some(Block {
+        start: ts(parts[0])?,
+        end: ts(parts[1])?,
+        last_activity: ts(parts[2])?,
+        tokens: parts[3].parse().ok()?,
+        cost_usd: parts[4].parse().ok()?,
+        messages: parts[5].parse().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::TokenUsage;
+
+    fn entry(timestamp: &str, input: u64) -> UsageEntry {
+        UsageEntry {
+            timestamp: at(timestamp),
+            model: "claude-sonnet-4-5".to_string(),
+            usage: TokenUsage {
+                input,
+                ..Default::default()
+            },
+            dedup_key: None,
+        }
+    }
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(timestamp)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn floors_block_start_to_the_hour() {
+        let entries = [
+            entry("2026-03-02T10:47:13Z", 100),
+            entry("2026-03-02T11:05:00Z", 50),
+        ];
+        let blocks = identify_blocks(&entries, &PricingTable::default());
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].start, at("2026-03-02T10:00:00Z"));
+        assert_eq!(blocks[0].end, at("2026-03-02T15:00:00Z"));
+        assert_eq!(blocks[0].last_activity, at("2026-03-02T11:05:00Z"));
+        assert_eq!((blocks[0].tokens, blocks[0].messages), (150, 2));
+    }
+
+    #[test]
+    fn idle_gap_starts_a_new_block() {
+        let entries = [
+            entry("2026-03-02T10:15:00Z", 100_000),
+            entry("2026-03-02T16:30:00Z", 10),
+        ];
+        let blocks = identify_blocks(&entries, &PricingTable::default());
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].cost_usd, 0.3);
+        assert_eq!(blocks[1].start, at("2026-03-02T16:00:00Z"));
+        assert_eq!(blocks[1].tokens, 10);
+    }
+
+    #[test]
+    fn expired_block_starts_a_new_one_despite_steady_activity() {
+        let entries = [
+            entry("2026-03-02T10:15:00Z", 1),
+            entry("2026-03-02T12:00:00Z", 1),
+            entry("2026-03-02T14:30:00Z", 1),
+            entry("2026-03-02T15:10:00Z", 1),
+        ];
+        let blocks = identify_blocks(&entries, &PricingTable::default());
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].messages, 3);
+        assert_eq!(blocks[1].start, at("2026-03-02T15:00:00Z"));
+        assert!(blocks[1].is_active(at("2026-03-02T19:59:59Z")));
+        assert!(!blocks[1].is_active(at("2026-03-02T20:00:00Z")));
+    }
+
+    #[test]
+    fn round_trips_cached_block() {
+        let entries = [entry("2026-03-02T10:47:13Z", 123_456)];
+        let block = identify_blocks(&entries, &PricingTable::default())
+            .pop()
+            .unwrap();
+        assert_eq!(parse_cached_block(&serialize_block(&block)), Some(block));
+
+        assert_eq!(parse_cached_block(""), None);
+        assert_eq!(parse_cached_block("1|2|3|4|5"), None);
+        assert_eq!(parse_cached_block("a|2|3|4|5|6"), None);
+    }
+}
@@ -0,0 +1,207 @@
+use chrono::{DateTime, Duration, Local, Utc};
+
+use crate::blocks::{self, Block};
+use crate::cache::Cache;
+use crate::pricing::PricingTable;
+use crate::transcript;
+use crate::usage_history;
+use crate::usage_limits::UsageLimitsInfo;
+
+/// Consumption rate over a sliding window of recent activity.
+#[derive(Debug, Default)]
+pub struct BurnRateInfo {
+    /// Tokens per minute (all token types)
+    pub tokens_per_minute: Option<f64>,
+    /// USD per hour at API prices
+    pub cost_per_hour: Option<f64>,
+    /// Active 5-hour block reconstructed from transcripts
+    pub block: Option<Block>,
+}
+
+/// Projected state of the current 5-hour block at its reset time.
+#[derive(Debug, Default)]
+pub struct BlockProjection {
+    /// Expected block cost at reset if the current burn rate holds
+    pub projected_cost: Option<f64>,
+    /// Expected block tokens at reset if the current burn rate holds
+    pub projected_tokens: Option<u64>,
+    /// When 5-hour utilization reaches 100%, if that happens before the reset
+    pub limit_at: Option<DateTime<Local>>,
+}
+
+/// Measure the burn rate over the last `window_minutes` of transcript activity.
+/// Falls back to the session average from stdin cost data when no transcripts
+/// are available.
+pub fn collect(
+    pricing: &PricingTable,
+    window_minutes: u64,
+    session_cost: Option<f64>,
+    session_duration_ms: Option<u64>,
+) -> BurnRateInfo {
+    let mut info = BurnRateInfo {
+        block: blocks::active_block(pricing),
+        ..Default::default()
+    };
+
+    if let Some((tpm, cph)) = transcript_rate(pricing, window_minutes) {
+        info.tokens_per_minute = Some(tpm);
+        info.cost_per_hour = Some(cph);
+        return info;
+    }
+
+    // Session average: total cost over total wall-clock duration
+    if let (Some(cost), Some(ms)) = (session_cost, session_duration_ms) {
+        if ms >= 60_000 && cost > 0.0 {
+            info.cost_per_hour = Some(cost / (ms as f64 / 3_600_000.0));
+        }
+    }
+
+    info
+}
+
+/// Tokens per minute and cost per hour over the sliding window.
+/// Cached for 30 seconds, like the active block.
+fn transcript_rate(pricing: &PricingTable, window_minutes: u64) -> Option<(f64, f64)> {
+    let cache = Cache::new();
+    let key = format!("burn_rate_{window_minutes}");
+
+    if let Some(cached) = cache.get(&key, 30) {
+        let (tpm, cph) = cached.trim().split_once('|')?;
+        return Some((tpm.parse().ok()?, cph.parse().ok()?));
+    }
+
+    let now = Utc::now();
+    let since = now - Duration::minutes(window_minutes.max(1) as i64);
+    let entries = transcript::recent_entries(since);
+
+    let rate = if entries.is_empty() {
+        None
+    } else {
+        // Measure from the first message in the window rather than the window
+        // start, so a burst after an idle period isn't diluted. Use at least
+        // one minute to keep a single message from producing absurd rates.
+        let first = entries[0].timestamp;
+        let minutes = ((now - first).num_seconds() as f64 / 60.0).max(1.0);
+        let tokens: u64 = entries.iter().map(|e| e.usage.total()).sum();
+        let cost: f64 = entries
+            .iter()
+            .filter_map(|e| pricing.cost(&e.model, &e.usage))
+            .sum();
+        Some((tokens as f64 / minutes, cost / minutes * 60.0))
+    };
+
+    cache.set(
+        &key,
+        &rate.map(|(t, c)| format!("{t}|{c}")).unwrap_or_default(),
+    );
+    rate
+}
+
+/// Project the current block to its reset time.
+///
+/// Cost and tokens extrapolate the block so far at the current burn rate.
+/// Limit exhaustion extrapolates 5-hour utilization, which is only
+/// available when the OAuth API reports a percentage.
+pub fn project(
+    rate: &BurnRateInfo,
+    usage: &UsageLimitsInfo,
+    session_cost: Option<f64>,
+) -> BlockProjection {
+    let mut proj = BlockProjection::default();
+    let now = Utc::now();
+
+    // Without a reset time there is nothing to project to, and the session
+    // cost alone isn't a projection
+    let resets_at = match usage
+        .five_hour_resets_at
+        .map(|r| r.with_timezone(&Utc))
+        .or_else(|| rate.block.as_ref().map(|b| b.end))
+    {
+        Some(r) => r,
+        None => return proj,
+    };
+    let hours_left = ((resets_at - now).num_seconds().max(0) as f64) / 3600.0;
+
+    let (cost_so_far, tokens_so_far) = match rate.block {
+        Some(ref b) => (Some(b.cost_usd), Some(b.tokens)),
+        None => (session_cost, None),
+    };
+
+    if let Some(cost) = cost_so_far {
+        proj.projected_cost = Some(cost + rate.cost_per_hour.unwrap_or(0.0) * hours_left);
+    }
+    if let (Some(tokens), Some(tpm)) = (tokens_so_far, rate.tokens_per_minute) {
+        proj.projected_tokens = Some(tokens + (tpm * hours_left * 60.0) as u64);
+    }
+
+    if let Some(pct) = usage.five_hour_percent {
+        proj.limit_at = limit_exhaustion(pct, resets_at, now)
+            .filter(|at| *at < resets_at)
+            .map(|at| at.with_timezone(&Local));
+    }
+
+    proj
+}
+
+/// Extrapolate when utilization reaches 100% from recorded samples in the
+/// current block, or from its average rate since the block started at 0%
+/// five hours before the reset when there is no recent history.
+fn limit_exhaustion(
+    percent: f64,
+    resets_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    if percent >= 100.0 {
+        return Some(now);
+    }
+
+    // Oldest reading from this block within the last hour
+    let (oldest_at, oldest_percent) = usage_history::load(now - Duration::hours(1))
+        .into_iter()
+        .filter(|s| {
+            s.five_hour_resets_at
+                .is_some_and(|r| (r - resets_at).num_minutes().abs() < 5)
+        })
+        .find_map(|s| s.five_hour_percent.map(|p| (s.at, p)))
+        .unwrap_or((resets_at - Duration::hours(5), 0.0));
+
+    let minutes = (now - oldest_at).num_seconds() as f64 / 60.0;
+    let slope = (percent - oldest_percent) / minutes; // percent per minute
+    if minutes < 5.0 || slope <= 0.0 {
+        return None;
+    }
+
+    let minutes_to_full = (100.0 - percent) / slope;
+    Some(now + Duration::seconds((minutes_to_full * 60.0) as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_projection_without_block_or_reset() {
+        let rate = BurnRateInfo {
+            cost_per_hour: Some(4.0),
+            ..Default::default()
+        };
+        let proj = project(&rate, &UsageLimitsInfo::default(), Some(1.5));
+        assert_eq!(proj.projected_cost, None);
+        assert_eq!(proj.limit_at, None);
+    }
+
+    #[test]
+    fn projects_session_cost_to_known_reset() {
+        let rate = BurnRateInfo {
+            cost_per_hour: Some(4.0),
+            ..Default::default()
+        };
+        let usage = UsageLimitsInfo {
+            five_hour_resets_at: Some(Local::now() + Duration::minutes(90)),
+            ..Default::default()
+        };
+        let cost = project(&rate, &usage, Some(1.5)).projected_cost.unwrap();
+        // 1.5 + 4.0 * ~1.5h, less the few ms since the reset was computed
+        assert!((cost - 7.5).abs() < 0.01, "{cost}");
+    }
+}
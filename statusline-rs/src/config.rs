@@ -1,4 +1,5 @@
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// Top-level configuration parsed from Config.toml.
@@ -25,6 +26,19 @@ pub struct Config {
     pub usage_limits: UsageLimitsConfig,
     #[serde(default)]
     pub session_info: SessionInfoConfig,
+    #[serde(default)]
+    pub burn_rate: BurnRateConfig,
+    #[serde(default)]
+    pub git_status: GitStatusConfig,
+    #[serde(default)]
+    pub worktrees: WorktreesConfig,
+    #[serde(default)]
+    pub containers: ContainersConfig,
+    #[serde(default)]
+    pub kubernetes: KubernetesConfig,
+    /// Per-model price overrides keyed by model id pattern (e.g. `[pricing.opus-4-6]`).
+    #[serde(default)]
+    pub pricing: HashMap<String, PricingOverride>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -68,7 +82,18 @@ pub struct FeaturesConfig {
     #[serde(default)]
     pub show_submodules: bool,
     #[serde(default)]
+    pub show_branch_diff: bool,
+    #[serde(default)]
+    pub show_remote: bool,
+    #[serde(default)]
+    pub show_kubernetes: bool,
+    #[serde(default)]
     pub show_prayer_times: bool,
+    #[serde(default = "bool_true")]
+    pub hide_submodules_when_empty: bool,
+    /// List each submodule on its own line instead of only the summary
+    #[serde(default)]
+    pub show_submodule_details: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -147,6 +172,12 @@ pub struct CacheDurations {
 pub struct LabelsConfig {
     #[serde(default = "default_mcp_label")]
     pub mcp: String,
+    #[serde(default = "default_commits_label")]
+    pub commits: String,
+    #[serde(default = "default_submodule_label")]
+    pub submodule: String,
+    #[serde(default = "default_branch_diff_label")]
+    pub branch_diff: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -183,6 +214,108 @@ pub struct SessionInfoConfig {
     pub id_length: usize,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct WorktreesConfig {
+    /// Collapse the list into a one-line summary beyond this many worktrees
+    #[serde(default = "default_worktrees_max_lines")]
+    pub max_lines: usize,
+    /// A worktree has a live Claude session if a transcript for it was
+    /// written within this many minutes
+    #[serde(default = "default_session_active_minutes")]
+    pub session_active_minutes: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ContainersConfig {
+    /// "auto", "docker", "podman" or "nerdctl"
+    #[serde(default = "default_container_runtime")]
+    pub runtime: String,
+    /// "all" shows every container; "project" shows only Compose containers
+    /// started from the repository (or cwd outside a repo)
+    #[serde(default = "default_container_scope")]
+    pub scope: String,
+    /// Name or image globs shown even when not part of the project
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Name or image globs never shown
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// List published container ports and local servers started from the project
+    #[serde(default = "bool_true")]
+    pub show_ports: bool,
+    /// Link ports to http://localhost:PORT (OSC 8 hyperlinks)
+    #[serde(default)]
+    pub port_links: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct KubernetesConfig {
+    /// Context name globs (case-insensitive) treated as production and shown in red
+    #[serde(default = "default_production_contexts")]
+    pub production_contexts: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BurnRateConfig {
+    /// Sliding window for tokens/min and $/hr, in minutes
+    #[serde(default = "default_burn_rate_window")]
+    pub window_minutes: u64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct GitStatusConfig {
+    /// Space-separated items; `{staged}`, `{unstaged}`, `{untracked}`, `{renamed}`,
+    /// `{conflicted}` and `{stashed}` are replaced by counts, and items whose
+    /// count is zero are dropped.
+    #[serde(default = "default_git_status_format")]
+    pub format: String,
+    /// Skip line-level diff stats when more files than this changed
+    #[serde(default = "default_diff_max_files")]
+    pub diff_max_files: usize,
+    /// Files larger than this count as binary in diff stats
+    #[serde(default = "default_diff_max_file_bytes")]
+    pub diff_max_file_bytes: u64,
+    /// Time budget for computing status; slower repos show `?`
+    #[serde(default = "default_git_status_timeout_ms")]
+    pub timeout_ms: u64,
+    /// Repo path globs (e.g. "~/work/monorepo*") where untracked files aren't scanned
+    #[serde(default)]
+    pub skip_untracked_paths: Vec<String>,
+    /// Repo path globs where file status isn't computed at all
+    #[serde(default)]
+    pub skip_status_paths: Vec<String>,
+    /// Branch to size the current branch against; detected from the
+    /// remote's HEAD when unset
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    /// Remote identifying the repository; defaults to the branch's tracking
+    /// remote, then `origin`
+    #[serde(default)]
+    pub remote: Option<String>,
+    /// Link the repository and branch to the web UI (OSC 8 hyperlinks)
+    #[serde(default)]
+    pub hyperlinks: bool,
+    /// Warn when the last fetch is older than this (0 disables)
+    #[serde(default = "default_stale_fetch_hours")]
+    pub stale_fetch_hours: u64,
+}
+
+/// Partial price override in USD per million tokens.
+/// Unset fields keep the built-in value for the pattern. A pattern that
+/// matches no built-in model must set all four base rates, or it is ignored.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct PricingOverride {
+    pub input: Option<f64>,
+    pub output: Option<f64>,
+    pub cache_write: Option<f64>,
+    pub cache_read: Option<f64>,
+    pub long_context_threshold: Option<u64>,
+    pub long_context_input: Option<f64>,
+    pub long_context_output: Option<f64>,
+    pub long_context_cache_write: Option<f64>,
+    pub long_context_cache_read: Option<f64>,
+}
+
 // Default value functions
 fn bool_true() -> bool { true }
 fn default_theme_name() -> String { "catppuccin".into() }
@@ -201,6 +334,9 @@ fn default_cache_git_status() -> u64 { 10 }
 fn default_cache_mcp() -> u64 { 120 }
 fn default_cache_git_branch() -> u64 { 10 }
 fn default_mcp_label() -> String { "MCP".into() }
+fn default_commits_label() -> String { "Commits:".into() }
+fn default_branch_diff_label() -> String { "vs ".into() }
+fn default_submodule_label() -> String { "SUB:".into() }
 fn default_ctx_emoji() -> String { "\u{1f9e0}".into() }
 fn default_warn_threshold() -> u8 { 50 }
 fn default_critical_threshold() -> u8 { 90 }
@@ -208,6 +344,17 @@ fn default_usage_critical() -> u8 { 80 }
 fn default_limit_label() -> String { "Limit:".into() }
 fn default_cache_ttl() -> u64 { 300 }
 fn default_id_length() -> usize { 8 }
+fn default_burn_rate_window() -> u64 { 15 }
+fn default_worktrees_max_lines() -> usize { 5 }
+fn default_session_active_minutes() -> u64 { 10 }
+fn default_container_runtime() -> String { "auto".into() }
+fn default_container_scope() -> String { "all".into() }
+fn default_production_contexts() -> Vec<String> { vec!["*prod*".into()] }
+fn default_diff_max_files() -> usize { 500 }
+fn default_git_status_timeout_ms() -> u64 { 1000 }
+fn default_stale_fetch_hours() -> u64 { 24 }
+fn default_diff_max_file_bytes() -> u64 { 1024 * 1024 }
+fn default_git_status_format() -> String { "+{staged} ~{unstaged} ?{untracked} \u{2261}{stashed}".into() }
 
 impl Default for Config {
     fn default() -> Self {
@@ -222,6 +369,12 @@ impl Default for Config {
             context_window: ContextWindowConfig::default(),
             usage_limits: UsageLimitsConfig::default(),
             session_info: SessionInfoConfig::default(),
+            burn_rate: BurnRateConfig::default(),
+            git_status: GitStatusConfig::default(),
+            worktrees: WorktreesConfig::default(),
+            containers: ContainersConfig::default(),
+            kubernetes: KubernetesConfig::default(),
+            pricing: HashMap::new(),
         }
     }
 }
@@ -248,7 +401,12 @@ impl Default for FeaturesConfig {
             show_cost_tracking: false,
             show_reset_info: false,
             show_submodules: false,
+            show_branch_diff: false,
+            show_remote: false,
+            show_kubernetes: false,
             show_prayer_times: false,
+            hide_submodules_when_empty: true,
+            show_submodule_details: false,
         }
     }
 }
@@ -303,6 +461,9 @@ impl Default for LabelsConfig {
     fn default() -> Self {
         Self {
             mcp: default_mcp_label(),
+            commits: default_commits_label(),
+            branch_diff: default_branch_diff_label(),
+            submodule: default_submodule_label(),
         }
     }
 }
@@ -339,6 +500,71 @@ impl Default for SessionInfoConfig {
     }
 }
 
+impl Default for WorktreesConfig {
+    fn default() -> Self {
+        Self {
+            max_lines: default_worktrees_max_lines(),
+            session_active_minutes: default_session_active_minutes(),
+        }
+    }
+}
+
+impl Default for ContainersConfig {
+    fn default() -> Self {
+        Self {
+            runtime: default_container_runtime(),
+            scope: default_container_scope(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            show_ports: true,
+            port_links: false,
+        }
+    }
+}
+
+impl Default for KubernetesConfig {
+    fn default() -> Self {
+        Self {
+            production_contexts: default_production_contexts(),
+        }
+    }
+}
+
+impl Default for BurnRateConfig {
+    fn default() -> Self {
+        Self {
+            window_minutes: default_burn_rate_window(),
+        }
+    }
+}
+
+impl Default for GitStatusConfig {
+    fn default() -> Self {
+        Self {
+            format: default_git_status_format(),
+            diff_max_files: default_diff_max_files(),
+            diff_max_file_bytes: default_diff_max_file_bytes(),
+            timeout_ms: default_git_status_timeout_ms(),
+            skip_untracked_paths: Vec::new(),
+            skip_status_paths: Vec::new(),
+            default_branch: None,
+            remote: None,
+            hyperlinks: false,
+            stale_fetch_hours: default_stale_fetch_hours(),
+        }
+    }
+}
+
+impl CacheConfig {
+    /// TTL for cached git status, from `cache.durations.git_status`.
+    pub fn git_status_ttl(&self) -> u64 {
+        self.durations
+            .as_ref()
+            .map(|d| d.git_status)
+            .unwrap_or_else(default_cache_git_status)
+    }
+}
+
 impl Config {
     /// Load configuration from the standard path or return defaults.
     pub fn load() -> Self {
@@ -1,12 +1,31 @@
+use serde_json::Value;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::thread;
+use std::time::Duration;
 
 use crate::cache::Cache;
+use crate::config::ContainersConfig;
+use crate::docker::{DockerClient, Endpoint};
+use crate::util::glob_match;
 
-/// Docker container status.
+/// Container status, from whichever runtime was found.
 #[derive(Debug, Default)]
 pub struct ContainerInfo {
     pub containers: Vec<Container>,
+    /// Runtime the containers belong to
+    pub runtime: Option<Runtime>,
+    /// Local servers listening on TCP, started from inside the project
+    pub local_ports: Vec<LocalPort>,
+}
+
+/// A TCP port listened on by a process running in the project.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalPort {
+    pub port: u16,
+    /// Process name from /proc/<pid>/comm (e.g. "node")
+    pub process: String,
 }
 
 #[derive(Debug)]
@@ -16,96 +35,653 @@ pub struct Container {
     pub image: String,         // e.g., "alpine:3.19"
     pub cpu_percent: Option<f64>,  // e.g., 0.5 for 0.5%
     pub mem_usage: Option<String>, // e.g., "12.5MiB / 7.67GiB"
+    pub health: Option<String>,    // "healthy", "unhealthy", "starting"; None without a healthcheck
+    pub exit_code: Option<i64>,    // last exit code, e.g. 137 after an OOM kill
+    pub restart_count: Option<u64>, // restarts by the restart policy; unknown from docker/nerdctl CLIs
+    pub compose_project: Option<String>,     // com.docker.compose.project label
+    pub compose_working_dir: Option<String>, // com.docker.compose.project.working_dir label
+    pub compose_service: Option<String>,     // com.docker.compose.service label
+    pub ports: Vec<u16>,                     // host ports published by the container
+}
+
+impl Container {
+    /// Memory in use, in bytes, parsed from `mem_usage`.
+    pub fn mem_bytes(&self) -> Option<u64> {
+        parse_mem_usage(self.mem_usage.as_deref()?).map(|(used, _)| used)
+    }
+
+    /// Compose service name, or the container name outside Compose.
+    pub fn display_name(&self) -> &str {
+        self.compose_service.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Whether the container was started by Compose from `root` or a
+    /// directory inside it, or under the project name Compose derives from it.
+    fn belongs_to(&self, root: &Path) -> bool {
+        if let Some(ref dir) = self.compose_working_dir {
+            return Path::new(dir).starts_with(root);
+        }
+        match (&self.compose_project, root.file_name()) {
+            (Some(project), Some(dir_name)) => *project == compose_project_name(&dir_name.to_string_lossy()),
+            _ => false,
+        }
+    }
+
+    fn matches_any(&self, patterns: &[String]) -> bool {
+        patterns
+            .iter()
+            .any(|p| glob_match(p, &self.name) || glob_match(p, &self.image))
+    }
+}
+
+/// Containers of one Compose project, with resource totals.
+#[derive(Debug)]
+pub struct ComposeGroup<'a> {
+    pub project: &'a str,
+    pub containers: Vec<&'a Container>,
+    /// Sum over running containers; None when no stats are available
+    pub cpu_percent: Option<f64>,
+    pub mem_bytes: Option<u64>,
+}
+
+impl ContainerInfo {
+    /// Split containers into Compose projects (in first-seen order) and
+    /// standalone containers.
+    pub fn grouped(&self) -> (Vec<ComposeGroup<'_>>, Vec<&Container>) {
+        let mut groups: Vec<ComposeGroup> = Vec::new();
+        let mut standalone = Vec::new();
+
+        for c in &self.containers {
+            let project = match c.compose_project.as_deref() {
+                Some(p) => p,
+                None => {
+                    standalone.push(c);
+                    continue;
+                }
+            };
+            let idx = match groups.iter().position(|g| g.project == project) {
+                Some(i) => i,
+                None => {
+                    groups.push(ComposeGroup {
+                        project,
+                        containers: Vec::new(),
+                        cpu_percent: None,
+                        mem_bytes: None,
+                    });
+                    groups.len() - 1
+                }
+            };
+            let group = &mut groups[idx];
+            group.containers.push(c);
+            if let Some(cpu) = c.cpu_percent {
+                group.cpu_percent = Some(group.cpu_percent.unwrap_or(0.0) + cpu);
+            }
+            if let Some(mem) = c.mem_bytes() {
+                group.mem_bytes = Some(group.mem_bytes.unwrap_or(0) + mem);
+            }
+        }
+
+        for g in &mut groups {
+            g.containers.sort_by_key(|c| c.display_name().to_string());
+        }
+        (groups, standalone)
+    }
+}
+
+/// Parse a `mem_usage` string like "12.5MiB / 7.67GiB" into used and limit
+/// bytes. Accepts binary (KiB, MiB, ...) and decimal (kB, MB, ...) units.
+pub fn parse_mem_usage(s: &str) -> Option<(u64, u64)> {
+    let (used, limit) = s.split_once('/')?;
+    Some((parse_size(used)?, parse_size(limit)?))
+}
+
+fn parse_size(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let multiplier: f64 = match unit.trim() {
+        "" | "B" => 1.0,
+        "KiB" => 1024.0,
+        "MiB" => 1024.0 * 1024.0,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        "TiB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        "kB" | "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        _ => return None,
+    };
+    Some((number * multiplier) as u64)
 }
 
-/// Collect information about ALL Docker containers (running + stopped).
-/// Uses caching (30s TTL) to avoid slow Docker calls on every refresh.
+/// Compose's default project name for a directory: lowercased, keeping
+/// only letters, digits, `_` and `-`.
+fn compose_project_name(dir_name: &str) -> String {
+    dir_name
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+        .collect()
+}
+
+/// Whether a container is relevant to `root` according to the config:
+/// the project's Compose containers (or all with `scope = "all"`), plus any
+/// matching `include`, minus any matching `exclude`.
+fn keep(c: &Container, cfg: &ContainersConfig, root: &Path) -> bool {
+    let in_scope = cfg.scope == "all" || c.belongs_to(root) || c.matches_any(&cfg.include);
+    in_scope && !c.matches_any(&cfg.exclude)
+}
+
+/// Cumulative CPU counters from one stats reading. One-shot stats carry no
+/// previous reading, so CPU % is computed against the last render's sample.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CpuSample {
+    pub total: u64,
+    pub system: u64,
+    pub online_cpus: u32,
+}
+
+/// Supported container runtimes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Runtime {
+    Docker,
+    Podman,
+    Nerdctl,
+}
+
+impl Runtime {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "docker" => Some(Self::Docker),
+            "podman" => Some(Self::Podman),
+            "nerdctl" => Some(Self::Nerdctl),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Docker => "docker",
+            Self::Podman => "podman",
+            Self::Nerdctl => "nerdctl",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::Docker => "\u{1f433}",  // 🐳
+            Self::Podman => "\u{1f9ad}",  // 🦭
+            Self::Nerdctl => "\u{1f4e6}", // 📦
+        }
+    }
+
+    /// Docker-compatible API sockets for this runtime, in order of preference.
+    fn sockets(&self) -> Vec<Endpoint> {
+        let env_host = match self {
+            Self::Docker => std::env::var("DOCKER_HOST").ok(),
+            Self::Podman => std::env::var("CONTAINER_HOST").ok(),
+            // nerdctl has no API socket
+            Self::Nerdctl => return Vec::new(),
+        };
+        if let Some(host) = env_host.filter(|h| !h.is_empty()) {
+            return Endpoint::parse(&host).into_iter().collect();
+        }
+
+        let mut paths = Vec::new();
+        match self {
+            Self::Docker => paths.push(PathBuf::from("/var/run/docker.sock")),
+            Self::Podman => {
+                // Rootless first, then the system service
+                if let Ok(dir) = std::env::var("XDG_RUNTIME_DIR") {
+                    paths.push(PathBuf::from(dir).join("podman/podman.sock"));
+                }
+                paths.push(PathBuf::from("/run/podman/podman.sock"));
+            }
+            Self::Nerdctl => {}
+        }
+        paths
+            .into_iter()
+            .filter(|p| p.exists())
+            .map(Endpoint::Unix)
+            .collect()
+    }
+}
+
+/// How to talk to a runtime: its Docker-compatible API, or its CLI.
+#[derive(Debug)]
+enum Backend {
+    Api(Runtime, DockerClient),
+    Cli(Runtime),
+}
+
+impl Backend {
+    fn runtime(&self) -> Runtime {
+        match self {
+            Self::Api(rt, _) | Self::Cli(rt) => *rt,
+        }
+    }
+}
+
+/// Backends to try, in order. `runtime = "auto"` tries Docker, then Podman,
+/// then nerdctl, preferring an API socket over the CLI for each.
+fn detect(preference: &str) -> Vec<Backend> {
+    let candidates: Vec<Runtime> = match Runtime::from_name(preference) {
+        Some(rt) => vec![rt],
+        None => vec![Runtime::Docker, Runtime::Podman, Runtime::Nerdctl],
+    };
+    let timeout = Duration::from_secs(2);
+
+    // An explicit DOCKER_HOST means the user picked a daemon
+    if preference == "auto" {
+        if let Ok(host) = std::env::var("DOCKER_HOST").map(|h| h.trim().to_string()) {
+            if !host.is_empty() {
+                let tls = ["DOCKER_TLS_VERIFY", "DOCKER_CERT_PATH"]
+                    .iter()
+                    .any(|v| std::env::var(v).is_ok_and(|x| !x.is_empty()));
+                return docker_host_backends(&host, tls, timeout);
+            }
+        }
+    }
+
+    let mut backends = Vec::new();
+    for rt in candidates {
+        if let Some(endpoint) = rt.sockets().into_iter().next() {
+            backends.push(Backend::Api(rt, DockerClient::new(endpoint, timeout)));
+        }
+        if in_path(rt.name()) {
+            backends.push(Backend::Cli(rt));
+        }
+    }
+    backends
+}
+
+/// Backends for an explicit `DOCKER_HOST`. The API client only speaks plain
+/// HTTP over unix:// and tcp://; ssh:// and TLS daemons are left to the
+/// docker CLI, which reads the same variables.
+fn docker_host_backends(host: &str, tls: bool, timeout: Duration) -> Vec<Backend> {
+    let mut backends = Vec::new();
+    if let Some(endpoint) = Endpoint::parse(host).filter(|_| !tls) {
+        backends.push(Backend::Api(Runtime::Docker, DockerClient::new(endpoint, timeout)));
+    }
+    backends.push(Backend::Cli(Runtime::Docker));
+    backends
+}
+
+/// Whether an executable named `bin` is on PATH.
+fn in_path(bin: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(bin).is_file()))
+        .unwrap_or(false)
+}
+
+/// Collect information about ALL containers (running + stopped) of the
+/// configured or detected runtime.
+/// Uses caching (30s TTL) to avoid querying the runtime on every refresh.
 /// For running containers, also fetches CPU and memory stats.
-pub fn collect() -> ContainerInfo {
+///
+/// Only containers kept for the project at `cwd` are returned, and only
+/// those are inspected; see `keep`.
+pub fn collect(cfg: &ContainersConfig, cwd: &Path) -> ContainerInfo {
+    // Compose records the directory it ran in; match it against the repo root
+    let root = git2::Repository::discover(cwd)
+        .ok()
+        .and_then(|r| r.workdir().map(|p| p.to_path_buf()))
+        .unwrap_or_else(|| cwd.to_path_buf());
+    let root = root.canonicalize().unwrap_or(root);
+    let mut info = collect_all(cfg, &root);
+
+    if cfg.show_ports {
+        info.local_ports = cached_local_listeners(&root);
+    }
+
+    info
+}
+
+/// Listeners under `root`, cached for 10 seconds per root; a /proc scan
+/// reads every process's fds, too slow to repeat on each render.
+fn cached_local_listeners(root: &Path) -> Vec<LocalPort> {
     let cache = Cache::new();
+    let cache_key = format!("local_ports_{}", root.display());
+
+    if let Some(cached) = cache.get(&cache_key, 10) {
+        return parse_cached_listeners(&cached);
+    }
+
+    let ports = local_listeners(root);
+    cache.set(&cache_key, &serialize_listeners(&ports));
+    ports
+}
+
+/// One `port|process` line per listener.
+fn serialize_listeners(ports: &[LocalPort]) -> String {
+    ports
+        .iter()
+        .map(|p| format!("{}|{}", p.port, p.process))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn parse_cached_listeners(cached: &str) -> Vec<LocalPort> {
+    cached
+        .lines()
+        .filter_map(|line| {
+            let (port, process) = line.split_once('|')?;
+            Some(LocalPort { port: port.parse().ok()?, process: process.to_string() })
+        })
+        .collect()
+}
+
+/// TCP ports in LISTEN state on loopback or all interfaces whose owning
+/// process has its cwd inside `root`, from /proc (Linux only; empty elsewhere).
+pub fn local_listeners(root: &Path) -> Vec<LocalPort> {
+    // Listening socket inode -> port
+    let mut listening: HashMap<u64, u16> = HashMap::new();
+    for table in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        if let Ok(content) = std::fs::read_to_string(table) {
+            listening.extend(content.lines().skip(1).filter_map(parse_listen_line));
+        }
+    }
+    if listening.is_empty() {
+        return Vec::new();
+    }
+
+    let mut ports: Vec<LocalPort> = Vec::new();
+    let procs = match std::fs::read_dir("/proc") {
+        Ok(p) => p,
+        Err(_) => return ports,
+    };
+    for entry in procs.flatten() {
+        let pid_dir = entry.path();
+        let is_pid = entry.file_name().to_string_lossy().chars().all(|c| c.is_ascii_digit());
+        // Other users' processes fail here, which is fine
+        let in_project = is_pid
+            && std::fs::read_link(pid_dir.join("cwd")).is_ok_and(|cwd| cwd.starts_with(root));
+        if !in_project {
+            continue;
+        }
+
+        let fds = match std::fs::read_dir(pid_dir.join("fd")) {
+            Ok(f) => f,
+            Err(_) => continue,
+        };
+        for fd in fds.flatten() {
+            let target = match std::fs::read_link(fd.path()) {
+                Ok(t) => t.to_string_lossy().to_string(),
+                Err(_) => continue,
+            };
+            let inode = target
+                .strip_prefix("socket:[")
+                .and_then(|t| t.strip_suffix(']'))
+                .and_then(|t| t.parse::<u64>().ok());
+            let port = match inode.and_then(|i| listening.get(&i)) {
+                Some(p) => *p,
+                None => continue,
+            };
+            if ports.iter().any(|p| p.port == port) {
+                continue;
+            }
+            let process = std::fs::read_to_string(pid_dir.join("comm"))
+                .map(|c| c.trim().to_string())
+                .unwrap_or_default();
+            ports.push(LocalPort { port, process });
+        }
+    }
+
+    ports.sort_by_key(|p| p.port);
+    ports
+}
+
+/// Parse a /proc/net/tcp{,6} row into (inode, port) if it's a listening
+/// socket bound to loopback or all interfaces.
+fn parse_listen_line(line: &str) -> Option<(u64, u16)> {
+    // sl local_address rem_address st tx:rx tr:when retrnsmt uid timeout inode
+    const LISTEN: &str = "0A";
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 10 || fields[3] != LISTEN {
+        return None;
+    }
+
+    let (addr, port) = fields[1].split_once(':')?;
+    let local = matches!(
+        addr,
+        // 0.0.0.0, 127.0.0.1 (little-endian hex), ::, ::1, ::ffff:127.0.0.1
+        "00000000"
+            | "0100007F"
+            | "00000000000000000000000000000000"
+            | "00000000000000000000000001000000"
+            | "0000000000000000FFFF00000100007F"
+    );
+    if !local {
+        return None;
+    }
+
+    Some((fields[9].parse().ok()?, u16::from_str_radix(port, 16).ok()?))
+}
+
+/// Containers of the runtime kept for `root`, cached for 30 seconds per root.
+fn collect_all(cfg: &ContainersConfig, root: &Path) -> ContainerInfo {
+    let cache = Cache::new();
+    let cache_key = format!("containers_{}_{}", cfg.runtime, root.display());
+    let wanted = |c: &Container| keep(c, cfg, root);
 
     // Check cache first (30 second TTL)
-    if let Some(cached) = cache.get("docker_containers", 30) {
+    if let Some(cached) = cache.get(&cache_key, 30) {
         if let Some(info) = parse_cached_output(&cached) {
             return info;
         }
     }
 
-    // Cache miss — fetch fresh data
-    let info = collect_fresh(&cache);
+    // Cache miss — use the first runtime that responds; an installed CLI
+    // whose daemon isn't running falls through to the next one
+    let info = detect(&cfg.runtime)
+        .into_iter()
+        .find_map(|backend| {
+            let mut info = match backend {
+                Backend::Api(_, ref client) => collect_from_api(client, &cache, &wanted)?,
+                Backend::Cli(rt) => collect_from_cli(rt.name(), &wanted)?,
+            };
+            info.runtime = Some(backend.runtime());
+            Some(info)
+        })
+        .unwrap_or_default();
+
+    cache.set(&cache_key, &serialize_container_info(&info));
     info
 }
 
-/// Fetch fresh Docker container data and cache it.
-fn collect_fresh(cache: &Cache) -> ContainerInfo {
+/// Query a Docker-compatible API, computing CPU % against the previous
+/// render's samples.
+fn collect_from_api(
+    client: &DockerClient,
+    cache: &Cache,
+    keep: &(dyn Fn(&Container) -> bool + Sync),
+) -> Option<ContainerInfo> {
+    let mut previous = cache
+        .get("docker_cpu_samples", 300)
+        .map(|c| parse_cpu_samples(&c))
+        .unwrap_or_default();
+
+    // None when the daemon isn't running or is unreachable
+    let (info, samples) = fetch(client, &previous, keep)?;
+
+    // Other projects' renders sample other containers; keep theirs too
+    previous.extend(samples);
+    cache.set("docker_cpu_samples", &serialize_cpu_samples(&previous));
+    Some(info)
+}
+
+/// List containers with a Docker-compatible CLI (`docker`, `podman`, `nerdctl`).
+fn collect_from_cli(bin: &str, keep: &dyn Fn(&Container) -> bool) -> Option<ContainerInfo> {
     let mut info = ContainerInfo::default();
 
     // Step 1: Get ALL containers (running + stopped)
-    let ps_output = match Command::new("docker")
-        .args(["ps", "-a", "--format", "{{.Names}}\t{{.Status}}\t{{.Image}}"])
-        .output()
-    {
-        Ok(o) => o,
-        Err(_) => return info, // Docker not installed or not running
+    let containers = if bin == "podman" {
+        list_podman(bin)?
+    } else {
+        list_cli(bin)?
     };
 
+    let mut running_containers = Vec::new();
+    for container in containers.into_iter().filter(|c| keep(c)) {
+        // Track running containers for stats collection
+        if container.status == "running" {
+            running_containers.push(container.name.clone());
+        }
+        info.containers.push(container);
+    }
+
+    // Step 2: If there are running containers, fetch their stats
+    if !running_containers.is_empty() {
+        let stats = fetch_cli_stats(bin, &running_containers);
+
+        // Match stats to containers by name
+        for container in &mut info.containers {
+            if let Some(stats) = stats.get(&container.name) {
+                container.cpu_percent = stats.cpu_percent;
+                container.mem_usage = stats.mem_usage.clone();
+            }
+        }
+    }
+
+    Some(info)
+}
+
+/// Containers from `ps` with a Go template, as docker and nerdctl print it.
+fn list_cli(bin: &str) -> Option<Vec<Container>> {
+    let ps_output = Command::new(bin)
+        .args([
+            "ps",
+            "-a",
+            "--format",
+            "{{.Names}}\t{{.Status}}\t{{.Image}}\t{{.Ports}}\t{{.Labels}}",
+        ])
+        .output()
+        .ok()?;
+
+    // Fails when the daemon isn't running
     if !ps_output.status.success() {
-        return info;
+        return None;
     }
 
     let stdout = String::from_utf8_lossy(&ps_output.stdout);
-    let mut running_containers = Vec::new();
+    let mut containers = Vec::new();
 
     // Parse container list
     for line in stdout.lines() {
-        let parts: Vec<&str> = line.splitn(3, '\t').collect();
-        if parts.len() == 3 {
+        let parts: Vec<&str> = line.splitn(5, '\t').collect();
+        if parts.len() >= 3 {
             let name = parts[0].to_string();
             let raw_status = parts[1];
             let status = simplify_status(raw_status);
             let image = parts[2].to_string();
+            let ports = parse_cli_ports(parts.get(3).copied().unwrap_or(""));
+            let labels = parse_cli_labels(parts.get(4).copied().unwrap_or(""));
 
-            // Track running containers for stats collection
-            if status == "running" {
-                running_containers.push(name.clone());
-            }
-
-            info.containers.push(Container {
+            containers.push(Container {
                 name,
                 status,
                 image,
                 cpu_percent: None,
                 mem_usage: None,
+                health: health_from_status(raw_status),
+                exit_code: exit_code_from_status(raw_status),
+                restart_count: None,
+                compose_project: labels.get(COMPOSE_PROJECT).cloned(),
+                compose_working_dir: labels.get(COMPOSE_WORKING_DIR).cloned(),
+                compose_service: labels.get(COMPOSE_SERVICE).cloned(),
+                ports,
             });
         }
     }
 
-    // Step 2: If there are running containers, fetch their stats
-    if !running_containers.is_empty() {
-        let stats = fetch_container_stats(&running_containers);
+    Some(containers)
+}
 
-        // Match stats to containers by name
-        for container in &mut info.containers {
-            if let Some(stats) = stats.get(&container.name) {
-                container.cpu_percent = stats.cpu_percent;
-                container.mem_usage = stats.mem_usage.clone();
-            }
-        }
+/// Containers from `podman ps --format json`. Podman renders `{{.Labels}}`
+/// as a Go map string, which can't be split reliably when values contain
+/// spaces, so its JSON output is used instead.
+fn list_podman(bin: &str) -> Option<Vec<Container>> {
+    let output = Command::new(bin)
+        .args(["ps", "-a", "--format", "json"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
     }
 
-    // Cache the result as serialized JSON-like format
-    let cached_output = serialize_container_info(&info);
-    cache.set("docker_containers", &cached_output);
+    let json: Value = serde_json::from_slice(&output.stdout).ok()?;
+    Some(parse_podman_ps(&json))
+}
 
-    info
+fn parse_podman_ps(json: &Value) -> Vec<Container> {
+    let entries = match json.as_array() {
+        Some(e) => e,
+        None => return Vec::new(),
+    };
+
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let text = |key: &str| entry.get(key).and_then(|v| v.as_str());
+            let name = entry.get("Names")?.get(0)?.as_str()?.to_string();
+            let raw_status = text("Status").unwrap_or("");
+            let status = text("State")
+                .map(|s| s.to_lowercase())
+                .unwrap_or_else(|| simplify_status(raw_status));
+            let label = |key: &str| {
+                entry
+                    .get("Labels")
+                    .and_then(|l| l.get(key))
+                    .and_then(|v| v.as_str())
+                    .map(|v| v.to_string())
+            };
+
+            let mut ports: Vec<u16> = entry
+                .get("Ports")
+                .and_then(|p| p.as_array())
+                .map(|list| {
+                    list.iter()
+                        .filter_map(|p| p.get("host_port")?.as_u64())
+                        .filter_map(|p| u16::try_from(p).ok())
+                        .filter(|p| *p > 0)
+                        .collect()
+                })
+                .unwrap_or_default();
+            ports.sort_unstable();
+            ports.dedup();
+
+            let exit_code = (status == "exited")
+                .then(|| entry.get("ExitCode").and_then(|c| c.as_i64()))
+                .flatten();
+
+            Some(Container {
+                name,
+                image: text("Image").unwrap_or("").to_string(),
+                cpu_percent: None,
+                mem_usage: None,
+                health: health_from_status(raw_status),
+                exit_code,
+                restart_count: entry.get("Restarts").and_then(|r| r.as_u64()),
+                compose_project: label(COMPOSE_PROJECT),
+                compose_working_dir: label(COMPOSE_WORKING_DIR),
+                compose_service: label(COMPOSE_SERVICE),
+                ports,
+                status,
+            })
+        })
+        .collect()
 }
 
 /// Fetch CPU and memory stats for running containers.
-fn fetch_container_stats(container_names: &[String]) -> HashMap<String, ContainerStats> {
+fn fetch_cli_stats(bin: &str, container_names: &[String]) -> HashMap<String, ContainerStats> {
     let mut stats_map = HashMap::new();
 
-    // Run docker stats for all running containers at once
-    let stats_output = match Command::new("docker")
+    // Run stats for all running containers at once
+    let stats_output = match Command::new(bin)
         .args([
             "stats",
             "--no-stream",
@@ -159,7 +735,7 @@ struct ContainerStats {
     mem_usage: Option<String>,
 }
 
-/// Simplify Docker status strings into standard states.
+/// Simplify CLI status strings (e.g. "Up 2 hours") into standard states.
 fn simplify_status(status: &str) -> String {
     let lower = status.to_lowercase();
 
@@ -183,10 +759,295 @@ fn simplify_status(status: &str) -> String {
     }
 }
 
+const COMPOSE_PROJECT: &str = "com.docker.compose.project";
+const COMPOSE_WORKING_DIR: &str = "com.docker.compose.project.working_dir";
+const COMPOSE_SERVICE: &str = "com.docker.compose.service";
+
+/// Host ports from `{{.Ports}}` output like
+/// "0.0.0.0:8080->80/tcp, :::8080->80/tcp, 5432/tcp" (unpublished ports have no `->`).
+fn parse_cli_ports(raw: &str) -> Vec<u16> {
+    let mut ports: Vec<u16> = raw
+        .split(',')
+        .filter_map(|p| p.split_once("->"))
+        .filter_map(|(host, _)| host.rsplit(':').next()?.trim().parse().ok())
+        .collect();
+    ports.sort_unstable();
+    ports.dedup();
+    ports
+}
+
+/// Parse `{{.Labels}}` output from docker and nerdctl: `k=v,k=v`.
+fn parse_cli_labels(raw: &str) -> HashMap<String, String> {
+    raw.trim()
+        .split(',')
+        .filter_map(|kv| kv.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// Exit code from a CLI status like "Exited (137) 2 minutes ago".
+fn exit_code_from_status(status: &str) -> Option<i64> {
+    let rest = status.split_once('(')?.1;
+    rest.split_once(')')?.0.trim().parse().ok()
+}
+
+/// Healthcheck state from a CLI status like "Up 5 minutes (healthy)".
+fn health_from_status(status: &str) -> Option<String> {
+    let lower = status.to_lowercase();
+    if lower.contains("(unhealthy)") {
+        Some("unhealthy".into())
+    } else if lower.contains("(healthy)") {
+        Some("healthy".into())
+    } else if lower.contains("starting)") {
+        Some("starting".into())
+    } else {
+        None
+    }
+}
+
+/// Query the Engine API: list all containers, then inspect each one passing
+/// `keep` and fetch one-shot stats for running ones, all concurrently.
+/// Returns the kept containers and this reading's CPU samples by name.
+pub fn fetch(
+    client: &DockerClient,
+    previous: &HashMap<String, CpuSample>,
+    keep: &(dyn Fn(&Container) -> bool + Sync),
+) -> Option<(ContainerInfo, HashMap<String, CpuSample>)> {
+    let list = client.get_json("/containers/json?all=1")?;
+    let entries = list.as_array()?;
+
+    let results: Vec<(Container, Option<CpuSample>)> = thread::scope(|scope| {
+        let handles: Vec<_> = entries
+            .iter()
+            .filter_map(|entry| {
+                let id = entry.get("Id")?.as_str()?;
+                let name = entry
+                    .get("Names")
+                    .and_then(|n| n.get(0))
+                    .and_then(|n| n.as_str())
+                    .map(|n| n.trim_start_matches('/'))
+                    .unwrap_or(id);
+                let state = entry.get("State").and_then(|s| s.as_str()).unwrap_or("unknown");
+                let image = entry.get("Image").and_then(|s| s.as_str()).unwrap_or("");
+                let label = |key: &str| {
+                    entry
+                        .get("Labels")
+                        .and_then(|l| l.get(key))
+                        .and_then(|v| v.as_str())
+                        .map(|v| v.to_string())
+                };
+
+                let container = Container {
+                    name: name.to_string(),
+                    status: state.to_string(),
+                    image: image.to_string(),
+                    cpu_percent: None,
+                    mem_usage: None,
+                    health: None,
+                    exit_code: None,
+                    restart_count: None,
+                    compose_project: label(COMPOSE_PROJECT),
+                    compose_working_dir: label(COMPOSE_WORKING_DIR),
+                    compose_service: label(COMPOSE_SERVICE),
+                    ports: api_ports(entry),
+                };
+                if !keep(&container) {
+                    return None;
+                }
+                Some(scope.spawn(move || fetch_details(client, id, container, previous)))
+            })
+            .collect();
+
+        handles.into_iter().filter_map(|h| h.join().ok()).collect()
+    });
+
+    let mut info = ContainerInfo::default();
+    let mut samples = HashMap::new();
+    for (container, sample) in results {
+        if let Some(sample) = sample {
+            samples.insert(container.name.clone(), sample);
+        }
+        info.containers.push(container);
+    }
+
+    Some((info, samples))
+}
+
+/// Host ports published by a container in the list response.
+fn api_ports(entry: &Value) -> Vec<u16> {
+    let mut ports: Vec<u16> = entry
+        .get("Ports")
+        .and_then(|p| p.as_array())
+        .map(|list| {
+            list.iter()
+                .filter_map(|p| p.get("PublicPort")?.as_u64())
+                .filter_map(|p| u16::try_from(p).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    // IPv4 and IPv6 bindings of the same port are listed separately
+    ports.sort_unstable();
+    ports.dedup();
+    ports
+}
+
+/// Inspect a container for its health, exit code and restart count, and
+/// read stats if it's running.
+fn fetch_details(
+    client: &DockerClient,
+    id: &str,
+    mut container: Container,
+    previous: &HashMap<String, CpuSample>,
+) -> (Container, Option<CpuSample>) {
+    let running = container.status == "running";
+
+    thread::scope(|scope| {
+        let stats = running.then(|| {
+            scope.spawn(|| client.get_json(&format!("/containers/{id}/stats?stream=false&one-shot=true")))
+        });
+
+        if let Some(inspect) = client.get_json(&format!("/containers/{id}/json")) {
+            container.health = inspect
+                .pointer("/State/Health/Status")
+                .and_then(|s| s.as_str())
+                .map(|s| s.to_string());
+            container.exit_code = inspect.pointer("/State/ExitCode").and_then(|v| v.as_i64());
+            container.restart_count = inspect.get("RestartCount").and_then(|v| v.as_u64());
+        }
+
+        let stats = match stats.and_then(|h| h.join().ok()).flatten() {
+            Some(s) => s,
+            None => return (container, None),
+        };
+
+        let sample = cpu_sample(&stats, "cpu_stats");
+        let baseline = cpu_sample(&stats, "precpu_stats")
+            .filter(|p| p.system > 0)
+            .or_else(|| previous.get(&container.name).copied());
+        container.cpu_percent = match (sample, baseline) {
+            (Some(now), Some(before)) => cpu_percent(&now, &before),
+            _ => None,
+        };
+        container.mem_usage = mem_usage(&stats);
+
+        (container, sample)
+    })
+}
+
+fn cpu_sample(stats: &Value, key: &str) -> Option<CpuSample> {
+    let cpu = stats.get(key)?;
+    let total = cpu.pointer("/cpu_usage/total_usage")?.as_u64()?;
+    let system = cpu.get("system_cpu_usage").and_then(|v| v.as_u64()).unwrap_or(0);
+    let online_cpus = cpu
+        .get("online_cpus")
+        .and_then(|v| v.as_u64())
+        .or_else(|| {
+            cpu.pointer("/cpu_usage/percpu_usage")
+                .and_then(|p| p.as_array())
+                .map(|p| p.len() as u64)
+        })
+        .unwrap_or(1) as u32;
+
+    Some(CpuSample {
+        total,
+        system,
+        online_cpus,
+    })
+}
+
+/// CPU % the way `docker stats` computes it: the container's share of
+/// system CPU time between two readings, scaled by the number of CPUs.
+fn cpu_percent(now: &CpuSample, before: &CpuSample) -> Option<f64> {
+    let cpu_delta = now.total.checked_sub(before.total)? as f64;
+    let system_delta = now.system.checked_sub(before.system)? as f64;
+    if system_delta <= 0.0 {
+        return None;
+    }
+    Some(cpu_delta / system_delta * now.online_cpus.max(1) as f64 * 100.0)
+}
+
+/// Memory in use over the limit (e.g. "12.5MiB / 7.67GiB"). Like
+/// `docker stats`, page cache that can be reclaimed is not counted.
+fn mem_usage(stats: &Value) -> Option<String> {
+    let mem = stats.get("memory_stats")?;
+    let usage = mem.get("usage")?.as_u64()?;
+    let limit = mem.get("limit").and_then(|v| v.as_u64()).unwrap_or(0);
+
+    // cgroup v2 reports inactive_file, v1 total_inactive_file
+    let inactive = ["inactive_file", "total_inactive_file"]
+        .iter()
+        .find_map(|k| mem.get("stats")?.get(*k)?.as_u64())
+        .unwrap_or(0);
+
+    Some(format!(
+        "{} / {}",
+        format_bytes(usage.saturating_sub(inactive)),
+        format_bytes(limit)
+    ))
+}
+
+/// Format a byte count with binary units and about three significant digits.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    let precision = if unit == 0 || value >= 100.0 {
+        0
+    } else if value >= 10.0 {
+        1
+    } else {
+        2
+    };
+    let s = format!("{:.*}", precision, value);
+    // "1.00GiB" reads better as "1GiB"
+    let s = if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        s
+    };
+    format!("{}{}", s, UNITS[unit])
+}
+
+/// Format: one line per container: name|total|system|cpus
+fn serialize_cpu_samples(samples: &HashMap<String, CpuSample>) -> String {
+    samples
+        .iter()
+        .map(|(name, s)| format!("{}|{}|{}|{}", name, s.total, s.system, s.online_cpus))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn parse_cpu_samples(cached: &str) -> HashMap<String, CpuSample> {
+    cached
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split('|').collect();
+            if parts.len() != 4 {
+                return None;
+            }
+            Some((
+                parts[0].to_string(),
+                CpuSample {
+                    total: parts[1].parse().ok()?,
+                    system: parts[2].parse().ok()?,
+                    online_cpus: parts[3].parse().ok()?,
+                },
+            ))
+        })
+        .collect()
+}
+
 /// Serialize ContainerInfo to a cacheable string format.
-/// Format: one line per container: name|status|image|cpu|mem
+/// Format: the runtime name, then one line per container:
+/// name|status|image|cpu|mem|health|exit code|restarts|compose project|compose working dir|
+/// compose service|ports (comma-separated)
 fn serialize_container_info(info: &ContainerInfo) -> String {
-    let mut lines = Vec::new();
+    let mut lines = vec![info.runtime.map(|r| r.name()).unwrap_or("").to_string()];
 
     for container in &info.containers {
         let cpu = container
@@ -197,14 +1058,28 @@ fn serialize_container_info(info: &ContainerInfo) -> String {
             .mem_usage
             .clone()
             .unwrap_or_else(|| "".to_string());
+        let health = container.health.as_deref().unwrap_or("");
+        let exit_code = container.exit_code.map(|c| c.to_string()).unwrap_or_default();
+        let restarts = container.restart_count.map(|c| c.to_string()).unwrap_or_default();
+        let project = container.compose_project.as_deref().unwrap_or("");
+        let working_dir = container.compose_working_dir.as_deref().unwrap_or("");
+        let service = container.compose_service.as_deref().unwrap_or("");
+        let ports: Vec<String> = container.ports.iter().map(|p| p.to_string()).collect();
 
         lines.push(format!(
-            "{}|{}|{}|{}|{}",
+            "{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}",
             container.name,
             container.status,
             container.image,
             cpu,
-            mem
+            mem,
+            health,
+            exit_code,
+            restarts,
+            project,
+            working_dir,
+            service,
+            ports.join(",")
         ));
     }
 
@@ -213,11 +1088,15 @@ fn serialize_container_info(info: &ContainerInfo) -> String {
 
 /// Parse cached output back into ContainerInfo.
 fn parse_cached_output(cached: &str) -> Option<ContainerInfo> {
-    let mut info = ContainerInfo::default();
+    let mut lines = cached.lines();
+    let mut info = ContainerInfo {
+        runtime: lines.next().and_then(Runtime::from_name),
+        ..Default::default()
+    };
 
-    for line in cached.lines() {
+    for line in lines {
         let parts: Vec<&str> = line.split('|').collect();
-        if parts.len() == 5 {
+        if parts.len() == 12 {
             let name = parts[0].to_string();
             let status = parts[1].to_string();
             let image = parts[2].to_string();
@@ -231,6 +1110,8 @@ fn parse_cached_output(cached: &str) -> Option<ContainerInfo> {
             } else {
                 Some(parts[4].to_string())
             };
+            let opt = |s: &str| if s.is_empty() { None } else { Some(s.to_string()) };
+            let health = opt(parts[5]);
 
             info.containers.push(Container {
                 name,
@@ -238,6 +1119,13 @@ fn parse_cached_output(cached: &str) -> Option<ContainerInfo> {
                 image,
                 cpu_percent,
                 mem_usage,
+                health,
+                exit_code: parts[6].parse().ok(),
+                restart_count: parts[7].parse().ok(),
+                compose_project: opt(parts[8]),
+                compose_working_dir: opt(parts[9]),
+                compose_service: opt(parts[10]),
+                ports: parts[11].split(',').filter_map(|p| p.parse().ok()).collect(),
             });
         }
     }
@@ -248,3 +1136,140 @@ fn parse_cached_output(cached: &str) -> Option<ContainerInfo> {
         Some(info)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_podman_ps_json() {
+        let json: Value = serde_json::from_str(
+            r#"[
+                {"Names": ["web"], "State": "running", "Status": "Up 2 minutes (healthy)",
+                 "Image": "nginx:latest", "ExitCode": 0, "Restarts": 1,
+                 "Labels": {"com.docker.compose.project": "shop",
+                            "com.docker.compose.service": "web",
+                            "description": "front end proxy"},
+                 "Ports": [{"host_port": 8080}, {"host_port": 8080}, {"host_port": 443}]},
+                {"Names": ["job"], "State": "exited", "Status": "Exited (2) 1 hour ago",
+                 "Image": "busybox", "ExitCode": 2, "Labels": null, "Ports": null}
+            ]"#,
+        )
+        .unwrap();
+
+        let containers = parse_podman_ps(&json);
+        assert_eq!(containers.len(), 2);
+
+        let web = &containers[0];
+        assert_eq!(web.name, "web");
+        assert_eq!(web.status, "running");
+        assert_eq!(web.health.as_deref(), Some("healthy"));
+        assert_eq!(web.exit_code, None);
+        assert_eq!(web.restart_count, Some(1));
+        assert_eq!(web.compose_project.as_deref(), Some("shop"));
+        assert_eq!(web.compose_service.as_deref(), Some("web"));
+        assert_eq!(web.ports, vec![443, 8080]);
+
+        let job = &containers[1];
+        assert_eq!(job.status, "exited");
+        assert_eq!(job.exit_code, Some(2));
+        assert_eq!(job.compose_project, None);
+        assert!(job.ports.is_empty());
+    }
+
+    #[test]
+    fn parses_cli_labels() {
+        let labels = parse_cli_labels("com.docker.compose.project=shop,tier=front end\n");
+        assert_eq!(labels.get(COMPOSE_PROJECT).map(String::as_str), Some("shop"));
+        assert_eq!(labels.get("tier").map(String::as_str), Some("front end"));
+        assert!(parse_cli_labels("").is_empty());
+    }
+
+    #[test]
+    fn parses_mem_usage_in_binary_and_decimal_units() {
+        assert_eq!(parse_mem_usage("12MiB / 1GiB"), Some((12 << 20, 1 << 30)));
+        assert_eq!(parse_mem_usage("5.5MB / 1GB"), Some((5_500_000, 1_000_000_000)));
+        assert_eq!(parse_mem_usage("512B/2KiB"), Some((512, 2048)));
+        assert_eq!(parse_mem_usage("1.5GiB / 0B"), Some((3 << 29, 0)));
+    }
+
+    #[test]
+    fn rejects_malformed_mem_usage() {
+        assert_eq!(parse_mem_usage("12MiB"), None);
+        assert_eq!(parse_mem_usage("-- / --"), None);
+        assert_eq!(parse_mem_usage("12XB / 1GiB"), None);
+    }
+
+    #[test]
+    fn parses_listening_loopback_and_wildcard_sockets() {
+        let v4 = "   0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41234 1 0000000000000000 100 0 0 10 0";
+        assert_eq!(parse_listen_line(v4), Some((41234, 8080)));
+        let any = "   1: 00000000:0016 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 1111 1";
+        assert_eq!(parse_listen_line(any), Some((1111, 22)));
+        let v6 = "   0: 00000000000000000000000001000000:0BB8 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 5555 1";
+        assert_eq!(parse_listen_line(v6), Some((5555, 3000)));
+    }
+
+    #[test]
+    fn skips_connected_and_external_sockets() {
+        let established = "   2: 0100007F:1F90 0100007F:D431 01 00000000:00000000 00:00000000 00000000  1000        0 777 1";
+        assert_eq!(parse_listen_line(established), None);
+        let lan = "   3: 0A00A8C0:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 888 1";
+        assert_eq!(parse_listen_line(lan), None);
+        let header = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode";
+        assert_eq!(parse_listen_line(header), None);
+    }
+
+    #[test]
+    fn parses_published_cli_ports() {
+        assert_eq!(
+            parse_cli_ports("0.0.0.0:8080->80/tcp, :::8080->80/tcp, 127.0.0.1:5433->5432/tcp"),
+            vec![5433, 8080]
+        );
+        assert!(parse_cli_ports("5432/tcp, 6379/tcp").is_empty());
+        assert!(parse_cli_ports("").is_empty());
+    }
+
+    #[test]
+    fn reads_public_api_ports() {
+        let entry: Value = serde_json::from_str(
+            r#"{"Ports": [
+                {"IP": "0.0.0.0", "PrivatePort": 80, "PublicPort": 8080, "Type": "tcp"},
+                {"IP": "::", "PrivatePort": 80, "PublicPort": 8080, "Type": "tcp"},
+                {"PrivatePort": 5432, "Type": "tcp"},
+                {"IP": "127.0.0.1", "PrivatePort": 443, "PublicPort": 8443, "Type": "tcp"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(api_ports(&entry), vec![8080, 8443]);
+        assert!(api_ports(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn round_trips_cached_listeners() {
+        let ports = vec![
+            LocalPort { port: 3000, process: "node".to_string() },
+            LocalPort { port: 8000, process: "python3".to_string() },
+        ];
+        assert_eq!(parse_cached_listeners(&serialize_listeners(&ports)), ports);
+        assert!(parse_cached_listeners("").is_empty());
+    }
+
+    #[test]
+    fn docker_host_falls_back_to_the_cli() {
+        let timeout = Duration::from_secs(1);
+        let kinds = |host: &str, tls: bool| -> Vec<&str> {
+            docker_host_backends(host, tls, timeout)
+                .iter()
+                .map(|b| match b {
+                    Backend::Api(..) => "api",
+                    Backend::Cli(_) => "cli",
+                })
+                .collect()
+        };
+        assert_eq!(kinds("unix:///run/docker.sock", false), ["api", "cli"]);
+        assert_eq!(kinds("tcp://10.0.0.5:2375", false), ["api", "cli"]);
+        assert_eq!(kinds("tcp://10.0.0.5:2376", true), ["cli"]);
+        assert_eq!(kinds("ssh://me@build-box", false), ["cli"]);
+    }
+}
@@ -0,0 +1,323 @@
+use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Where the Engine API listens.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Endpoint {
+    Unix(PathBuf),
+    /// Plain HTTP `host:port`; TLS daemons are not supported
+    Tcp(String),
+}
+
+impl Endpoint {
+    /// Parse a `DOCKER_HOST` value (`unix:///path` or `tcp://host:port`).
+    pub fn parse(host: &str) -> Option<Self> {
+        if let Some(path) = host.strip_prefix("unix://") {
+            Some(Self::Unix(PathBuf::from(path)))
+        } else if let Some(addr) = host.strip_prefix("tcp://") {
+            Some(Self::Tcp(addr.trim_end_matches('/').to_string()))
+        } else if host.starts_with('/') {
+            Some(Self::Unix(PathBuf::from(host)))
+        } else {
+            None
+        }
+    }
+}
+
+/// Minimal HTTP/1.0 client for the Docker Engine API. Each request uses its
+/// own connection, so one client can be shared across threads.
+#[derive(Debug, Clone)]
+pub struct DockerClient {
+    endpoint: Endpoint,
+    timeout: Duration,
+}
+
+impl DockerClient {
+    pub fn new(endpoint: Endpoint, timeout: Duration) -> Self {
+        Self { endpoint, timeout }
+    }
+
+    /// GET `path` and parse the JSON body. None on connection errors and
+    /// non-2xx responses.
+    pub fn get_json(&self, path: &str) -> Option<Value> {
+        let (status, body) = self.get(path)?;
+        if !(200..300).contains(&status) {
+            return None;
+        }
+        serde_json::from_slice(&body).ok()
+    }
+
+    fn get(&self, path: &str) -> Option<(u16, Vec<u8>)> {
+        // HTTP/1.0 makes the daemon close the connection after the response,
+        // so reading to EOF is enough even without a Content-Length
+        let request = format!("GET {path} HTTP/1.0\r\nHost: docker\r\nAccept: application/json\r\n\r\n");
+        let mut raw = Vec::new();
+
+        match self.endpoint {
+            Endpoint::Unix(ref socket) => {
+                let mut stream = UnixStream::connect(socket).ok()?;
+                stream.set_read_timeout(Some(self.timeout)).ok()?;
+                stream.set_write_timeout(Some(self.timeout)).ok()?;
+                stream.write_all(request.as_bytes()).ok()?;
+                stream.read_to_end(&mut raw).ok()?;
+            }
+            Endpoint::Tcp(ref addr) => {
+                // An unreachable host would otherwise block for the OS
+                // connect timeout, holding up the whole render
+                let mut stream = addr
+                    .to_socket_addrs()
+                    .ok()?
+                    .find_map(|a| TcpStream::connect_timeout(&a, self.timeout).ok())?;
+                stream.set_read_timeout(Some(self.timeout)).ok()?;
+                stream.set_write_timeout(Some(self.timeout)).ok()?;
+                stream.write_all(request.as_bytes()).ok()?;
+                stream.read_to_end(&mut raw).ok()?;
+            }
+        }
+
+        parse_response(&raw)
+    }
+}
+
+/// Split a raw HTTP response into status code and body, decoding chunked
+/// transfer encoding and honoring Content-Length when present.
+fn parse_response(raw: &[u8]) -> Option<(u16, Vec<u8>)> {
+    let header_end = raw.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let head = String::from_utf8_lossy(&raw[..header_end]);
+    let body = &raw[header_end + 4..];
+
+    let mut lines = head.lines();
+    let status = lines.next()?.split_whitespace().nth(1)?.parse().ok()?;
+
+    let mut chunked = false;
+    let mut length: Option<usize> = None;
+    for line in lines {
+        let (name, value) = match line.split_once(':') {
+            Some(kv) => kv,
+            None => continue,
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.eq_ignore_ascii_case("chunked");
+        } else if name.eq_ignore_ascii_case("content-length") {
+            length = value.parse().ok();
+        }
+    }
+
+    let body = if chunked {
+        decode_chunked(body)?
+    } else {
+        match length {
+            Some(n) => body.get(..n)?.to_vec(),
+            None => body.to_vec(),
+        }
+    };
+
+    Some((status, body))
+}
+
+fn decode_chunked(mut data: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    loop {
+        let line_end = data.windows(2).position(|w| w == b"\r\n")?;
+        let size_str = String::from_utf8_lossy(&data[..line_end]);
+        // Chunk extensions (`;name=value`) are allowed after the size
+        let size_hex = size_str.split(';').next()?.trim();
+        let size = usize::from_str_radix(size_hex, 16).ok()?;
+        data = &data[line_end + 2..];
+        if size == 0 {
+            return Some(out);
+        }
+        out.extend_from_slice(data.get(..size)?);
+        data = data.get(size + 2..)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::containers;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::net::UnixListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// Serve canned responses keyed by request path on a temporary socket.
+    /// Unknown paths get a 404. Requested paths are recorded in the log.
+    fn fake_daemon(
+        name: &str,
+        routes: Vec<(&'static str, String)>,
+    ) -> (DockerClient, Arc<Mutex<Vec<String>>>) {
+        let socket = std::env::temp_dir().join(format!(
+            "statusline-test-{}-{}.sock",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&socket);
+        let listener = UnixListener::bind(&socket).unwrap();
+        let routes: HashMap<&str, String> = routes.into_iter().collect();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let requests = Arc::clone(&log);
+
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let routes = routes.clone();
+                let requests = Arc::clone(&requests);
+                thread::spawn(move || {
+                    let mut reader = BufReader::new(&stream);
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).unwrap();
+                    // Drain headers
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).unwrap() > 2 {
+                        line.clear();
+                    }
+
+                    let path = request_line.split_whitespace().nth(1).unwrap_or("");
+                    requests.lock().unwrap().push(path.to_string());
+                    let response = match routes.get(path) {
+                        Some(body) => format!(
+                            "HTTP/1.0 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                            body.len(),
+                            body
+                        ),
+                        None => "HTTP/1.0 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_string(),
+                    };
+                    (&stream).write_all(response.as_bytes()).unwrap();
+                });
+            }
+        });
+
+        let client = DockerClient::new(Endpoint::Unix(socket), Duration::from_secs(2));
+        (client, log)
+    }
+
+    #[test]
+    fn parses_docker_host() {
+        assert_eq!(
+            Endpoint::parse("unix:///run/user/1000/docker.sock"),
+            Some(Endpoint::Unix(PathBuf::from("/run/user/1000/docker.sock")))
+        );
+        assert_eq!(
+            Endpoint::parse("tcp://127.0.0.1:2375"),
+            Some(Endpoint::Tcp("127.0.0.1:2375".into()))
+        );
+        assert_eq!(Endpoint::parse("ssh://user@host"), None);
+    }
+
+    #[test]
+    fn decodes_chunked_and_sized_bodies() {
+        let chunked = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n[1,2\r\n2;ext=1\r\n,3\r\n1\r\n]\r\n0\r\n\r\n";
+        assert_eq!(parse_response(chunked), Some((200, b"[1,2,3]".to_vec())));
+
+        let sized = b"HTTP/1.0 404 Not Found\r\nContent-Length: 2\r\n\r\n{}trailing";
+        assert_eq!(parse_response(sized), Some((404, b"{}".to_vec())));
+    }
+
+    #[test]
+    fn get_json_over_unix_socket() {
+        let (client, _) = fake_daemon("get", vec![("/_ping", r#"{"ok":true}"#.into())]);
+        assert_eq!(client.get_json("/_ping"), Some(serde_json::json!({"ok": true})));
+        assert_eq!(client.get_json("/missing"), None);
+    }
+
+    #[test]
+    fn collects_containers_with_health_and_stats() {
+        let list = r#"[
+            {"Id":"aaa","Names":["/web"],"Image":"nginx:1.27","State":"running","Status":"Up 2 hours (healthy)",
+             "Ports":[{"IP":"0.0.0.0","PrivatePort":80,"PublicPort":8080,"Type":"tcp"},
+                      {"IP":"::","PrivatePort":80,"PublicPort":8080,"Type":"tcp"},{"PrivatePort":443,"Type":"tcp"}],
+             "Labels":{"com.docker.compose.project":"shop","com.docker.compose.project.working_dir":"/src/shop"}},
+            {"Id":"bbb","Names":["/job"],"Image":"alpine","State":"exited","Status":"Exited (137) 1 hour ago"}
+        ]"#;
+        let inspect_web = r#"{"State":{"Status":"running","ExitCode":0,"Health":{"Status":"healthy"}},"RestartCount":0}"#;
+        let inspect_job = r#"{"State":{"Status":"exited","ExitCode":137},"RestartCount":3}"#;
+        let stats_web = r#"{
+            "cpu_stats":{"cpu_usage":{"total_usage":300},"system_cpu_usage":2000,"online_cpus":2},
+            "precpu_stats":{"cpu_usage":{"total_usage":100},"system_cpu_usage":1000},
+            "memory_stats":{"usage":15728640,"limit":1073741824,"stats":{"inactive_file":2621440}}
+        }"#;
+
+        let (client, _) = fake_daemon(
+            "collect",
+            vec![
+                ("/containers/json?all=1", list.into()),
+                ("/containers/aaa/json", inspect_web.into()),
+                ("/containers/bbb/json", inspect_job.into()),
+                ("/containers/aaa/stats?stream=false&one-shot=true", stats_web.into()),
+            ],
+        );
+
+        let (info, samples) = containers::fetch(&client, &HashMap::new(), &|_| true).unwrap();
+        assert_eq!(info.containers.len(), 2);
+
+        let web = &info.containers[0];
+        assert_eq!(web.name, "web");
+        assert_eq!(web.status, "running");
+        assert_eq!(web.image, "nginx:1.27");
+        assert_eq!(web.health.as_deref(), Some("healthy"));
+        // (300 - 100) / (2000 - 1000) * 2 CPUs
+        assert_eq!(web.cpu_percent, Some(40.0));
+        assert_eq!(web.mem_usage.as_deref(), Some("12.5MiB / 1GiB"));
+        assert_eq!(web.mem_bytes(), Some(13_107_200));
+        assert_eq!(web.compose_project.as_deref(), Some("shop"));
+        assert_eq!(web.compose_working_dir.as_deref(), Some("/src/shop"));
+        assert_eq!(web.ports, vec![8080]);
+        assert!(samples.contains_key("web"));
+
+        let job = &info.containers[1];
+        assert_eq!(job.status, "exited");
+        assert_eq!(job.health, None);
+        assert_eq!(job.exit_code, Some(137));
+        assert_eq!(job.restart_count, Some(3));
+        assert_eq!(job.cpu_percent, None);
+        assert!(job.ports.is_empty());
+    }
+
+    #[test]
+    fn skips_details_for_filtered_containers() {
+        let list = r#"[
+            {"Id":"aaa","Names":["/web"],"Image":"nginx","State":"running","Status":"Up 1 minute"},
+            {"Id":"bbb","Names":["/other"],"Image":"redis","State":"running","Status":"Up 1 minute"}
+        ]"#;
+        let (client, log) = fake_daemon(
+            "filtered",
+            vec![
+                ("/containers/json?all=1", list.into()),
+                ("/containers/aaa/json", r#"{"State":{"Status":"running"},"RestartCount":2}"#.into()),
+            ],
+        );
+
+        let (info, _) = containers::fetch(&client, &HashMap::new(), &|c| c.name == "web").unwrap();
+        assert_eq!(info.containers.len(), 1);
+        assert_eq!(info.containers[0].name, "web");
+        assert_eq!(info.containers[0].restart_count, Some(2));
+        assert!(!log.lock().unwrap().iter().any(|p| p.contains("bbb")));
+    }
+
+    #[test]
+    fn unreachable_tcp_daemon_times_out() {
+        // TEST-NET-1 is never routed, so only the connect timeout ends this
+        let client = DockerClient::new(
+            Endpoint::Tcp("192.0.2.1:2375".into()),
+            Duration::from_millis(200),
+        );
+        let started = std::time::Instant::now();
+        assert_eq!(client.get_json("/_ping"), None);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn unreachable_daemon_yields_nothing() {
+        let client = DockerClient::new(
+            Endpoint::Unix(PathBuf::from("/nonexistent/docker.sock")),
+            Duration::from_millis(100),
+        );
+        assert!(containers::fetch(&client, &HashMap::new(), &|_| true).is_none());
+    }
+}
@@ -0,0 +1,227 @@
+use crate::config::KubernetesConfig;
+use crate::util::glob_match;
+use std::path::PathBuf;
+use yaml_rust2::{Yaml, YamlLoader};
+
+/// Active Kubernetes context from the kubeconfig.
+#[derive(Debug)]
+pub struct KubeInfo {
+    pub context: String,
+    /// Namespace set on the context, or "default"
+    pub namespace: String,
+    /// Context matches one of `production_contexts`
+    pub production: bool,
+}
+
+/// The parts of a kubeconfig we read.
+#[derive(Debug)]
+struct Kubeconfig {
+    current_context: Option<String>,
+    contexts: Vec<ContextEntry>,
+}
+
+/// One entry of the kubeconfig `contexts` list.
+#[derive(Debug)]
+struct ContextEntry {
+    name: String,
+    /// `context.namespace` of the entry
+    namespace: Option<String>,
+}
+
+/// Read the current context and namespace from the kubeconfig without
+/// calling kubectl. None when there is no kubeconfig or no current context.
+pub fn collect(cfg: &KubernetesConfig) -> Option<KubeInfo> {
+    let files: Vec<Kubeconfig> = kubeconfig_paths()
+        .iter()
+        .filter_map(|p| std::fs::read_to_string(p).ok())
+        .filter_map(|content| parse(&content))
+        .collect();
+    resolve(&files, cfg)
+}
+
+/// Merge the kubeconfig files in `KUBECONFIG` order. With several files the
+/// first one to set a value wins, same as kubectl's merge rules.
+fn resolve(files: &[Kubeconfig], cfg: &KubernetesConfig) -> Option<KubeInfo> {
+    let context = files
+        .iter()
+        .filter_map(|f| f.current_context.clone())
+        .find(|c| !c.is_empty())?;
+    let namespace = files
+        .iter()
+        .flat_map(|f| &f.contexts)
+        .find(|c| c.name == context)
+        .and_then(|c| c.namespace.clone())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| "default".to_string());
+
+    let lower = context.to_lowercase();
+    let production = cfg
+        .production_contexts
+        .iter()
+        .any(|p| glob_match(&p.to_lowercase(), &lower));
+
+    Some(KubeInfo {
+        context,
+        namespace,
+        production,
+    })
+}
+
+/// `KUBECONFIG` (a path list), or ~/.kube/config.
+fn kubeconfig_paths() -> Vec<PathBuf> {
+    match std::env::var_os("KUBECONFIG") {
+        Some(list) if !list.is_empty() => std::env::split_paths(&list)
+            .filter(|p| !p.as_os_str().is_empty())
+            .collect(),
+        _ => dirs::home_dir()
+            .map(|h| vec![h.join(".kube/config")])
+            .unwrap_or_default(),
+    }
+}
+
+/// Parse a kubeconfig file; None if it isn't valid YAML. JSON kubeconfigs
+/// parse too, since JSON is valid YAML.
+fn parse(content: &str) -> Option<Kubeconfig> {
+    let docs = YamlLoader::load_from_str(content).ok()?;
+    let doc = docs.first()?;
+    let text = |v: &Yaml| v.as_str().map(|s| s.to_string());
+
+    // kubectl writes `contexts: null` for an empty list
+    let contexts = doc["contexts"]
+        .as_vec()
+        .map(|list| {
+            list.iter()
+                .filter_map(|entry| {
+                    Some(ContextEntry {
+                        name: text(&entry["name"])?,
+                        namespace: text(&entry["context"]["namespace"]),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(Kubeconfig {
+        current_context: text(&doc["current-context"]),
+        contexts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KUBECTL: &str = r#"apiVersion: v1
+clusters:
+- cluster:
+    certificate-authority-data: LS0tLS1CRUdJTg==
+    server: https://127.0.0.1:6443
+  name: kind-dev
+contexts:
+- context:
+    cluster: kind-dev
+    namespace: payments
+    user: kind-dev
+  name: kind-dev
+- context:
+    cluster: eks
+    user: eks
+  name: arn:aws:eks:us-east-1:123456789012:cluster/prod-main
+current-context: kind-dev
+kind: Config
+preferences: {}
+users:
+- name: kind-dev
+  user:
+    namespace: not-a-context-namespace
+    token: abc
+"#;
+
+    fn config() -> KubernetesConfig {
+        KubernetesConfig {
+            production_contexts: vec!["*prod*".to_string()],
+        }
+    }
+
+    fn resolve_all(contents: &[&str]) -> Option<KubeInfo> {
+        let files: Vec<Kubeconfig> = contents.iter().filter_map(|c| parse(c)).collect();
+        resolve(&files, &config())
+    }
+
+    #[test]
+    fn reads_kubectl_written_config() {
+        let info = resolve_all(&[KUBECTL]).unwrap();
+        assert_eq!(info.context, "kind-dev");
+        assert_eq!(info.namespace, "payments");
+        assert!(!info.production);
+    }
+
+    #[test]
+    fn picks_the_current_one_of_several_contexts() {
+        let config = KUBECTL.replace(
+            "current-context: kind-dev",
+            "current-context: arn:aws:eks:us-east-1:123456789012:cluster/prod-main",
+        );
+        let info = resolve_all(&[&config]).unwrap();
+        assert_eq!(info.context, "arn:aws:eks:us-east-1:123456789012:cluster/prod-main");
+        // No namespace on that context, and `users[].user.namespace` is not it
+        assert_eq!(info.namespace, "default");
+        assert!(info.production);
+    }
+
+    #[test]
+    fn missing_namespace_or_context_falls_back() {
+        let config = "contexts:\n- name: bare\ncurrent-context: bare\n";
+        assert_eq!(resolve_all(&[config]).unwrap().namespace, "default");
+
+        assert!(resolve_all(&["contexts: null\ncurrent-context: \"\"\n"]).is_none());
+        assert!(resolve_all(&["apiVersion: v1\nkind: Config\n"]).is_none());
+        assert!(resolve_all(&[]).is_none());
+    }
+
+    #[test]
+    fn first_kubeconfig_to_set_a_value_wins() {
+        let first = "contexts:\n- name: dev\n  context:\n    namespace: first\n";
+        let second = "current-context: dev\ncontexts:\n- name: dev\n  context:\n    namespace: second\n";
+        let third = "current-context: prod\n";
+
+        let info = resolve_all(&[first, second, third]).unwrap();
+        assert_eq!(info.context, "dev");
+        assert_eq!(info.namespace, "first");
+
+        let info = resolve_all(&[third, second]).unwrap();
+        assert_eq!(info.context, "prod");
+        assert_eq!(info.namespace, "default");
+    }
+
+    #[test]
+    fn handles_quoted_and_commented_values() {
+        let config = r#"# written by hand
+contexts:
+- name: "staging" # the shared one
+  context: {cluster: stg, namespace: 'web # api'}
+current-context: 'staging'   # switch with kubectx
+"#;
+        let info = resolve_all(&[config]).unwrap();
+        assert_eq!(info.context, "staging");
+        assert_eq!(info.namespace, "web # api");
+    }
+
+    #[test]
+    fn reads_json_kubeconfig() {
+        let config = r#"{"apiVersion": "v1", "kind": "Config",
+            "contexts": [{"name": "gke_prod", "context": {"cluster": "gke", "namespace": "api"}}],
+            "current-context": "gke_prod"}"#;
+        let info = resolve_all(&[config]).unwrap();
+        assert_eq!(info.context, "gke_prod");
+        assert_eq!(info.namespace, "api");
+        assert!(info.production);
+    }
+
+    #[test]
+    fn skips_unparseable_files() {
+        assert!(parse("contexts: [unterminated").is_none());
+        let info = resolve_all(&["{not yaml", KUBECTL]).unwrap();
+        assert_eq!(info.context, "kind-dev");
+    }
+}
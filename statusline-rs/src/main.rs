@@ -1,16 +1,25 @@
+mod blocks;
+mod burn_rate;
 mod cache;
 mod config;
 mod containers;
 mod context;
+mod docker;
 mod git;
+mod kube;
 mod mcp;
+mod pricing;
 mod session;
 mod stdin_data;
 mod teams;
 mod theme;
+mod transcript;
+mod usage_history;
 mod usage_limits;
+mod util;
 mod worktrees;
 
+use chrono::Timelike;
 use std::path::PathBuf;
 use std::sync::mpsc;
 use std::thread;
@@ -21,13 +30,19 @@ use theme::Theme;
 /// All collected data from parallel module execution.
 struct StatusData {
     git: Option<git::GitInfo>,
+    commits: Option<git::CommitsInfo>,
+    branch_diff: Option<git::BranchDiff>,
+    submodules: git::SubmodulesInfo,
     session: session::SessionInfo,
     mcp: mcp::McpInfo,
     context: context::ContextInfo,
     usage: usage_limits::UsageLimitsInfo,
     containers: containers::ContainerInfo,
+    kube: Option<kube::KubeInfo>,
     worktrees: worktrees::WorktreeInfo,
     teams: teams::TeamsInfo,
+    burn_rate: burn_rate::BurnRateInfo,
+    projection: burn_rate::BlockProjection,
     /// Session cumulative lines added (from stdin cost data)
     lines_added: Option<u64>,
     /// Session cumulative lines removed (from stdin cost data)
@@ -41,6 +56,12 @@ struct StatusData {
 }
 
 fn main() {
+    // Subcommands (e.g. `statusline usage history`) run instead of rendering
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(run_command(&args));
+    }
+
     // Read stdin data from Claude Code first
     let stdin = stdin_data::read_stdin();
 
@@ -56,16 +77,62 @@ fn main() {
         .or_else(|| stdin.cwd.as_deref().map(PathBuf::from))
         .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
 
-    let data = collect_all(&cwd, &stdin);
+    let pricing = pricing::PricingTable::new(&cfg.pricing);
+
+    let data = collect_all(&cwd, &stdin, &cfg, &pricing);
     let output = format_statusline(&cfg, &theme, &data, &cwd);
 
     print!("{output}");
 }
 
+/// Run a CLI subcommand and return the process exit code.
+fn run_command(args: &[String]) -> i32 {
+    let words: Vec<&str> = args.iter().map(String::as_str).collect();
+    match words.as_slice() {
+        ["usage", "history", rest @ ..] => {
+            let mut days = 7;
+            let mut sparkline = false;
+            let mut iter = rest.iter();
+            while let Some(arg) = iter.next() {
+                match *arg {
+                    "--sparkline" => sparkline = true,
+                    "--days" => match iter.next().and_then(|d| d.parse().ok()) {
+                        Some(d) => days = d,
+                        None => {
+                            eprintln!("--days expects a number");
+                            return 2;
+                        }
+                    },
+                    other => {
+                        eprintln!("unknown option: {other}");
+                        return 2;
+                    }
+                }
+            }
+            usage_history::print_history(days, sparkline);
+            0
+        }
+        _ => {
+            eprintln!("usage: statusline [usage history [--days N] [--sparkline]]");
+            2
+        }
+    }
+}
+
 /// Collect data from all modules concurrently using std::thread.
-fn collect_all(cwd: &PathBuf, stdin: &stdin_data::StdinData) -> StatusData {
+fn collect_all(
+    cwd: &PathBuf,
+    stdin: &stdin_data::StdinData,
+    cfg: &Config,
+    pricing: &pricing::PricingTable,
+) -> StatusData {
     let cwd_git = cwd.clone();
+    let git_cfg = cfg.git_status.clone();
+    let git_ttl = cfg.cache.git_status_ttl();
     let cwd_wt = cwd.clone();
+    let pricing_usage = pricing.clone();
+    let pricing_burn = pricing.clone();
+    let pricing_models = pricing.clone();
 
     let (tx, rx) = mpsc::channel::<(&str, Box<dyn std::any::Any + Send>)>();
 
@@ -83,7 +150,7 @@ fn collect_all(cwd: &PathBuf, stdin: &stdin_data::StdinData) -> StatusData {
     // Spawn threads for I/O-bound modules
     let tx_git = tx.clone();
     thread::spawn(move || {
-        let result = git::collect(&cwd_git);
+        let result = git::collect(&cwd_git, &git_cfg, git_ttl);
         let _ = tx_git.send(("git", Box::new(result)));
     });
 
@@ -95,19 +162,24 @@ fn collect_all(cwd: &PathBuf, stdin: &stdin_data::StdinData) -> StatusData {
 
     let tx_usage = tx.clone();
     thread::spawn(move || {
-        let result = usage_limits::collect();
+        let result = usage_limits::collect(&pricing_usage);
         let _ = tx_usage.send(("usage", Box::new(result)));
     });
 
     let tx_containers = tx.clone();
+    let containers_cfg = cfg.containers.clone();
+    let cwd_containers = cwd.clone();
     thread::spawn(move || {
-        let result = containers::collect();
+        let result = containers::collect(&containers_cfg, &cwd_containers);
         let _ = tx_containers.send(("containers", Box::new(result)));
     });
 
     let tx_wt = tx.clone();
+    let wt_git_cfg = cfg.git_status.clone();
+    let wt_cfg = cfg.worktrees.clone();
+    let wt_ttl = cfg.cache.git_status_ttl();
     thread::spawn(move || {
-        let result = worktrees::collect(&cwd_wt);
+        let result = worktrees::collect(&cwd_wt, &wt_git_cfg, &wt_cfg, wt_ttl);
         let _ = tx_wt.send(("worktrees", Box::new(result)));
     });
 
@@ -117,17 +189,79 @@ fn collect_all(cwd: &PathBuf, stdin: &stdin_data::StdinData) -> StatusData {
         let _ = tx_teams.send(("teams", Box::new(result)));
     });
 
+    if let Some(path) = stdin.transcript_path.clone() {
+        let tx_models = tx.clone();
+        thread::spawn(move || {
+            let result = session::collect_model_usage(&path, &pricing_models);
+            let _ = tx_models.send(("model_usage", Box::new(result)));
+        });
+    }
+
+    if cfg.features.show_cost_tracking {
+        let tx_burn = tx.clone();
+        let window = cfg.burn_rate.window_minutes;
+        thread::spawn(move || {
+            let result = burn_rate::collect(&pricing_burn, window, cost_usd, duration_ms);
+            let _ = tx_burn.send(("burn_rate", Box::new(result)));
+        });
+    }
+
+    if cfg.features.show_commits {
+        let tx_commits = tx.clone();
+        let cwd_commits = cwd.clone();
+        let session_start = session::session_start(stdin);
+        thread::spawn(move || {
+            let result = git::collect_commits(&cwd_commits, session_start);
+            let _ = tx_commits.send(("commits", Box::new(result)));
+        });
+    }
+
+    if cfg.features.show_branch_diff {
+        let tx_branch = tx.clone();
+        let cwd_branch = cwd.clone();
+        let branch_cfg = cfg.git_status.clone();
+        let branch_ttl = cfg.cache.git_status_ttl();
+        thread::spawn(move || {
+            let result = git::collect_branch_diff(&cwd_branch, &branch_cfg, branch_ttl);
+            let _ = tx_branch.send(("branch_diff", Box::new(result)));
+        });
+    }
+
+    if cfg.features.show_kubernetes {
+        let tx_kube = tx.clone();
+        let kube_cfg = cfg.kubernetes.clone();
+        thread::spawn(move || {
+            let result = kube::collect(&kube_cfg);
+            let _ = tx_kube.send(("kube", Box::new(result)));
+        });
+    }
+
+    if cfg.features.show_submodules {
+        let tx_sub = tx.clone();
+        let cwd_sub = cwd.clone();
+        thread::spawn(move || {
+            let result = git::collect_submodules(&cwd_sub);
+            let _ = tx_sub.send(("submodules", Box::new(result)));
+        });
+    }
+
     drop(tx);
 
     let mut data = StatusData {
         git: None,
+        commits: None,
+        branch_diff: None,
+        submodules: git::SubmodulesInfo::default(),
         session: session_data,
         mcp: mcp::McpInfo::default(),
         context: context_data,
         usage: usage_limits::UsageLimitsInfo::default(),
         containers: containers::ContainerInfo::default(),
+        kube: None,
         worktrees: worktrees::WorktreeInfo::default(),
         teams: teams::TeamsInfo::default(),
+        burn_rate: burn_rate::BurnRateInfo::default(),
+        projection: burn_rate::BlockProjection::default(),
         lines_added,
         lines_removed,
         cost_usd,
@@ -142,6 +276,21 @@ fn collect_all(cwd: &PathBuf, stdin: &stdin_data::StdinData) -> StatusData {
                     data.git = *v;
                 }
             }
+            "commits" => {
+                if let Ok(v) = value.downcast::<Option<git::CommitsInfo>>() {
+                    data.commits = *v;
+                }
+            }
+            "branch_diff" => {
+                if let Ok(v) = value.downcast::<Option<git::BranchDiff>>() {
+                    data.branch_diff = *v;
+                }
+            }
+            "submodules" => {
+                if let Ok(v) = value.downcast::<git::SubmodulesInfo>() {
+                    data.submodules = *v;
+                }
+            }
             "mcp" => {
                 if let Ok(v) = value.downcast::<mcp::McpInfo>() {
                     data.mcp = *v;
@@ -157,6 +306,11 @@ fn collect_all(cwd: &PathBuf, stdin: &stdin_data::StdinData) -> StatusData {
                     data.containers = *v;
                 }
             }
+            "kube" => {
+                if let Ok(v) = value.downcast::<Option<kube::KubeInfo>>() {
+                    data.kube = *v;
+                }
+            }
             "worktrees" => {
                 if let Ok(v) = value.downcast::<worktrees::WorktreeInfo>() {
                     data.worktrees = *v;
@@ -167,10 +321,25 @@ fn collect_all(cwd: &PathBuf, stdin: &stdin_data::StdinData) -> StatusData {
                     data.teams = *v;
                 }
             }
+            "model_usage" => {
+                if let Ok(v) = value.downcast::<Vec<session::ModelUsage>>() {
+                    data.session.model_usage = *v;
+                }
+            }
+            "burn_rate" => {
+                if let Ok(v) = value.downcast::<burn_rate::BurnRateInfo>() {
+                    data.burn_rate = *v;
+                }
+            }
             _ => {}
         }
     }
 
+    // Projection needs both the burn rate and the usage limits
+    if cfg.features.show_cost_tracking {
+        data.projection = burn_rate::project(&data.burn_rate, &data.usage, data.cost_usd);
+    }
+
     data
 }
 
@@ -183,19 +352,19 @@ fn format_statusline(cfg: &Config, theme: &Theme, data: &StatusData, cwd: &PathB
     let mut lines: Vec<String> = Vec::new();
 
     // LINE 1: ~/path (branch) 📁
-    lines.push(format_line1(theme, data, cwd));
+    lines.push(format_line1(cfg, theme, data, cwd));
 
     // LINE 2: 🧠 Model │ 📄 +N • -N │ CC:version │ Ctx: pct%
     lines.push(format_line2(cfg, theme, data));
 
     // LINE 3: MCP:connected/total: servers │ ⏱ 5H ... • 7DAY ...
-    let line3 = format_line3(theme, data);
+    let line3 = format_line3(cfg, theme, data);
     if !line3.is_empty() {
         lines.push(line3);
     }
 
     // LINE 4: 🌳 branch [N worktrees] + containers + teams
-    let line4 = format_line4(theme, data);
+    let line4 = format_line4(cfg, theme, data);
     if !line4.is_empty() {
         lines.push(line4);
     }
@@ -204,25 +373,279 @@ fn format_statusline(cfg: &Config, theme: &Theme, data: &StatusData, cwd: &PathB
 }
 
 /// Line 1: ~/path (branch) 📁
-fn format_line1(theme: &Theme, data: &StatusData, cwd: &PathBuf) -> String {
+fn format_line1(cfg: &Config, theme: &Theme, data: &StatusData, cwd: &PathBuf) -> String {
     let r = theme.reset;
     let folder = shorten_path(cwd);
 
     if let Some(ref git) = data.git {
-        let status_icon = if git.is_clean {
+        let status_icon = if git.status_unknown {
+            format!("{}?{}", theme.dim, r)
+        } else if git.is_clean {
             format!("{}\u{2713}{}", theme.green, r)
         } else {
             format!("{}\u{2717}{}", theme.yellow, r)
         };
+        let counts = if git.status_unknown {
+            String::new()
+        } else {
+            format_status_counts(&cfg.git_status.format, git)
+        };
+        let counts = if counts.is_empty() {
+            counts
+        } else {
+            format!(" {}{}{}", theme.yellow, counts, r)
+        };
+        let commits = data
+            .commits
+            .as_ref()
+            .map(|c| format_commits(cfg, theme, c))
+            .unwrap_or_default();
+        let branch_diff = data
+            .branch_diff
+            .as_ref()
+            .map(|b| format_branch_diff(cfg, theme, b))
+            .unwrap_or_default();
+        let submodules = if cfg.features.show_submodules {
+            format_submodules(cfg, theme, &data.submodules)
+        } else {
+            String::new()
+        };
+        let remote = if cfg.features.show_remote {
+            git.remote
+                .as_ref()
+                .map(|rem| format_remote(cfg, theme, rem))
+                .unwrap_or_default()
+        } else {
+            String::new()
+        };
+        let branch = match git.remote {
+            // A detached HEAD has no branch page to link to
+            Some(ref rem) if cfg.git_status.hyperlinks && !git.detached => {
+                hyperlink(&rem.branch_url(&git.branch), &git.branch)
+            }
+            _ => git.branch.clone(),
+        };
         format!(
-            "{}{}{}  {}({}){} {} \u{1f4c1}",
-            theme.blue, folder, r, theme.green, git.branch, r, status_icon
+            "{}{}{}{}  {}({}){}{}{}{} {}{} \u{1f4c1}{}{}{}",
+            theme.blue,
+            folder,
+            r,
+            remote,
+            theme.green,
+            branch,
+            r,
+            format_upstream(theme, git),
+            format_fetch_age(cfg, theme, git),
+            format_operation(theme, git),
+            status_icon,
+            counts,
+            commits,
+            branch_diff,
+            submodules
         )
     } else {
         format!("{}{}{} \u{1f4c1}", theme.blue, folder, r)
     }
 }
 
+/// Hosting icon and repository slug, e.g. ` 🐙 owner/name`.
+fn format_remote(cfg: &Config, theme: &Theme, remote: &git::RemoteInfo) -> String {
+    let slug = format!("{}/{}", remote.owner, remote.name);
+    let slug = if cfg.git_status.hyperlinks {
+        hyperlink(&remote.web_url(), &slug)
+    } else {
+        slug
+    };
+    format!(" {} {}{}{}", remote.kind.icon(), theme.dim, slug, theme.reset)
+}
+
+/// Commits today and this session, plus the last commit's subject and age
+/// (e.g. `  Commits:3 (2 session) · "Fix parser" 2h ago`).
+fn format_commits(cfg: &Config, theme: &Theme, commits: &git::CommitsInfo) -> String {
+    let r = theme.reset;
+    let session = commits
+        .session
+        .filter(|n| *n > 0)
+        .map(|n| format!(" ({} session)", n))
+        .unwrap_or_default();
+    let mut s = format!(
+        "  {}{}{}{}{}",
+        theme.teal, cfg.labels.commits, commits.today, session, r
+    );
+
+    if let (Some(subject), Some(time)) = (&commits.last_subject, commits.last_time) {
+        let secs = (chrono::Local::now() - time).num_seconds();
+        s.push_str(&format!(
+            " {}\u{00b7} \"{}\" {} ago{}",
+            theme.dim,
+            truncate(subject, 40),
+            format_age(secs),
+            r
+        ));
+    }
+
+    s
+}
+
+/// Size of the branch against the default branch,
+/// e.g. `  vs origin/main ↑3 · 12 files +340 -20`.
+fn format_branch_diff(cfg: &Config, theme: &Theme, diff: &git::BranchDiff) -> String {
+    let r = theme.reset;
+    let mut s = format!(
+        "  {}{}{} \u{2191}{}{}",
+        theme.teal, cfg.labels.branch_diff, diff.base, diff.ahead, r
+    );
+
+    if diff.files > 0 {
+        s.push_str(&format!(
+            " {}\u{00b7} {} file{}{}",
+            theme.dim,
+            diff.files,
+            if diff.files == 1 { "" } else { "s" },
+            r
+        ));
+        if let (Some(added), Some(deleted)) = (diff.lines_added, diff.lines_deleted) {
+            s.push_str(&format!(
+                " {}+{}{} {}-{}{}",
+                theme.green, added, r, theme.red, deleted, r
+            ));
+        }
+    }
+
+    s
+}
+
+/// Submodule summary, e.g. `  SUB:3 (1 uninit, 1 dirty, 1 ≠)`.
+fn format_submodules(cfg: &Config, theme: &Theme, sub: &git::SubmodulesInfo) -> String {
+    let r = theme.reset;
+    if sub.submodules.is_empty() {
+        if cfg.features.hide_submodules_when_empty {
+            return String::new();
+        }
+        return format!("  {}{}--{}", theme.dim, cfg.labels.submodule, r);
+    }
+
+    let mut issues: Vec<String> = Vec::new();
+    if sub.uninitialized > 0 {
+        issues.push(format!("{} uninit", sub.uninitialized));
+    }
+    if sub.dirty > 0 {
+        issues.push(format!("{} dirty", sub.dirty));
+    }
+    if sub.out_of_sync > 0 {
+        issues.push(format!("{} \u{2260}", sub.out_of_sync));
+    }
+
+    if issues.is_empty() {
+        format!("  {}{}{}{}", theme.teal, cfg.labels.submodule, sub.submodules.len(), r)
+    } else {
+        format!(
+            "  {}{}{} ({}){}",
+            theme.yellow,
+            cfg.labels.submodule,
+            sub.submodules.len(),
+            issues.join(", "),
+            r
+        )
+    }
+}
+
+/// Expand the compact status template (e.g. `+2 ~3 ?1 ≡1`), dropping
+/// items whose count is zero.
+fn format_status_counts(template: &str, git: &git::GitInfo) -> String {
+    let counts = [
+        ("{staged}", git.staged),
+        ("{unstaged}", git.unstaged),
+        ("{untracked}", git.untracked),
+        ("{renamed}", git.renamed),
+        ("{conflicted}", git.conflicted),
+        ("{stashed}", git.stashed),
+    ];
+
+    template
+        .split_whitespace()
+        .filter_map(|item| {
+            let mut out = item.to_string();
+            for (placeholder, count) in counts {
+                if out.contains(placeholder) {
+                    if count == 0 {
+                        return None;
+                    }
+                    out = out.replace(placeholder, &count.to_string());
+                }
+            }
+            Some(out)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// In-progress operation and conflicts (e.g. ` REBASE 3/7 ⚠2 conflicts`).
+fn format_operation(theme: &Theme, git: &git::GitInfo) -> String {
+    let r = theme.reset;
+    let mut s = String::new();
+
+    let label = git.operation.label();
+    if !label.is_empty() {
+        let progress = git
+            .rebase_progress
+            .map(|(step, total)| format!(" {}/{}", step, total))
+            .unwrap_or_default();
+        s.push_str(&format!(" {}{}{}{}{}", theme.bold, theme.orange, label, progress, r));
+    }
+
+    if git.conflicted > 0 {
+        let noun = if git.conflicted == 1 { "conflict" } else { "conflicts" };
+        s.push_str(&format!(
+            " {}{}\u{26a0} {} {}{}",
+            theme.bold, theme.red, git.conflicted, noun, r
+        ));
+    }
+
+    s
+}
+
+/// Ahead/behind the upstream (` ↑2 ↓1`), or a marker when there is none.
+fn format_upstream(theme: &Theme, git: &git::GitInfo) -> String {
+    let r = theme.reset;
+    match git.upstream_status {
+        git::UpstreamStatus::Tracking => {
+            let mut s = String::new();
+            if git.ahead > 0 {
+                s.push_str(&format!(" {}\u{2191}{}{}", theme.cyan, git.ahead, r));
+            }
+            if git.behind > 0 {
+                s.push_str(&format!(" {}\u{2193}{}{}", theme.orange, git.behind, r));
+            }
+            s
+        }
+        git::UpstreamStatus::Gone => format!(" {}\u{2191}gone{}", theme.red, r),
+        git::UpstreamStatus::None => format!(" {}\u{2191}none{}", theme.dim, r),
+        git::UpstreamStatus::NotApplicable => String::new(),
+    }
+}
+
+/// Warning when ahead/behind may be outdated, e.g. ` ⟳3d` after three days
+/// without a fetch. Only shown for branches that track a remote.
+fn format_fetch_age(cfg: &Config, theme: &Theme, git: &git::GitInfo) -> String {
+    let threshold = cfg.git_status.stale_fetch_hours;
+    if threshold == 0 || git.upstream_status != git::UpstreamStatus::Tracking {
+        return String::new();
+    }
+
+    match git.last_fetch {
+        Some(at) => {
+            let secs = (chrono::Local::now() - at).num_seconds();
+            if secs < threshold as i64 * 3600 {
+                return String::new();
+            }
+            format!(" {}\u{27f3}{}{}", theme.yellow, format_age(secs), theme.reset)
+        }
+        // Never fetched since cloning
+        None => String::new(),
+    }
+}
+
 /// Line 2: 🧠 Model │ 📄 +N • -N │ CC:version │ Ctx: pct% │ $cost │ duration
 fn format_line2(cfg: &Config, theme: &Theme, data: &StatusData) -> String {
     let r = theme.reset;
@@ -250,12 +673,14 @@ fn format_line2(cfg: &Config, theme: &Theme, data: &StatusData) -> String {
             theme.green, added, r, theme.red, removed, r
         ));
     } else if let Some(ref git) = data.git {
-        if !git.is_clean {
-            let total_added = git.added + git.modified;
-            parts.push(format!(
-                "\u{1f4c4} {}+{}{} \u{2022} {}-{}{}",
-                theme.green, total_added, r, theme.red, git.deleted, r
-            ));
+        // No session data: fall back to uncommitted line changes in the repo
+        if let (Some(added), Some(deleted)) = (git.lines_added, git.lines_deleted) {
+            if added > 0 || deleted > 0 {
+                parts.push(format!(
+                    "\u{1f4c4} {}+{}{} \u{2022} {}-{}{}",
+                    theme.green, added, r, theme.red, deleted, r
+                ));
+            }
         }
     }
 
@@ -288,6 +713,12 @@ fn format_line2(cfg: &Config, theme: &Theme, data: &StatusData) -> String {
         }
     }
 
+    // Per-model cost breakdown, once the session has used more than one model
+    let breakdown = format_model_breakdown(theme, &data.session.model_usage);
+    if !breakdown.is_empty() {
+        parts.push(breakdown);
+    }
+
     // Session duration
     if let Some(ms) = data.duration_ms {
         let secs = ms / 1000;
@@ -301,8 +732,32 @@ fn format_line2(cfg: &Config, theme: &Theme, data: &StatusData) -> String {
     parts.join(&sep)
 }
 
+/// Per-model session cost, e.g. `opus $3.10 · sonnet $0.40`.
+/// Empty when the session used a single model family.
+fn format_model_breakdown(theme: &Theme, usage: &[session::ModelUsage]) -> String {
+    // Group by short name so e.g. two Sonnet versions show as one entry
+    let mut by_short: Vec<(&str, f64)> = Vec::new();
+    for m in usage {
+        match by_short.iter_mut().find(|(name, _)| *name == m.model_short) {
+            Some((_, cost)) => *cost += m.cost_usd,
+            None => by_short.push((&m.model_short, m.cost_usd)),
+        }
+    }
+
+    if by_short.len() < 2 {
+        return String::new();
+    }
+
+    by_short.sort_by(|a, b| b.1.total_cmp(&a.1));
+    by_short
+        .iter()
+        .map(|(name, cost)| format!("{}{} ${:.2}{}", theme.dim, name, cost, theme.reset))
+        .collect::<Vec<_>>()
+        .join(" \u{00b7} ")
+}
+
 /// Line 3: MCP:connected/total: servers │ ⏱ 5H at HH:MM (remaining) pct% • 7DAY time (pct%)
-fn format_line3(theme: &Theme, data: &StatusData) -> String {
+fn format_line3(cfg: &Config, theme: &Theme, data: &StatusData) -> String {
     let r = theme.reset;
     let sep = format!(" {}\u{2502}{} ", theme.dim, r);
     let mut major_parts: Vec<String> = Vec::new();
@@ -350,11 +805,16 @@ fn format_line3(theme: &Theme, data: &StatusData) -> String {
             .five_hour_remaining
             .as_deref()
             .unwrap_or("?");
-        let pct_str = data
-            .usage
-            .five_hour_percent
-            .map(|p| format!(" {:.0}%", p))
-            .unwrap_or_default();
+        let pct_str = match data.usage.five_hour_percent {
+            Some(p) => format!(" {:.0}%", p),
+            // Offline: block reconstructed from transcripts, show what it consumed
+            None => match (data.usage.five_hour_tokens, data.usage.five_hour_cost) {
+                (Some(tokens), Some(cost)) => {
+                    format!(" {} ${:.2}", format_tokens(tokens), cost)
+                }
+                _ => String::new(),
+            },
+        };
         usage_parts.push(format!(
             "\u{23f1} 5H at {} ({}){}",
             reset, remaining, pct_str
@@ -374,6 +834,43 @@ fn format_line3(theme: &Theme, data: &StatusData) -> String {
         major_parts.push(usage_parts.join(" \u{2022} "));
     }
 
+    // Burn rate and block projection
+    if cfg.features.show_cost_tracking {
+        let mut cost_parts: Vec<String> = Vec::new();
+
+        if let Some(cph) = data.burn_rate.cost_per_hour {
+            let tpm = data
+                .burn_rate
+                .tokens_per_minute
+                .map(|t| format!("{}/min ", format_tokens(t as u64)))
+                .unwrap_or_default();
+            cost_parts.push(format!("{}\u{1f525}{}${:.2}/hr{}", theme.orange, tpm, cph, r));
+        }
+
+        if let Some(cost) = data.projection.projected_cost {
+            let tokens = data
+                .projection
+                .projected_tokens
+                .map(|t| format!(" ({})", format_tokens(t)))
+                .unwrap_or_default();
+            cost_parts.push(format!("{}\u{1f4c8}${:.2}{}{}", theme.magenta, cost, tokens, r));
+        }
+
+        if let Some(at) = data.projection.limit_at {
+            cost_parts.push(format!(
+                "{}\u{26a0} limit at {:02}:{:02}{}",
+                theme.red,
+                at.hour(),
+                at.minute(),
+                r
+            ));
+        }
+
+        if !cost_parts.is_empty() {
+            major_parts.push(cost_parts.join(" "));
+        }
+    }
+
     if major_parts.is_empty() {
         return String::new();
     }
@@ -381,54 +878,70 @@ fn format_line3(theme: &Theme, data: &StatusData) -> String {
     major_parts.join(&sep)
 }
 
-/// Line 4+: Worktrees (each on own line) + containers + teams
-fn format_line4(theme: &Theme, data: &StatusData) -> String {
+/// Line 4+: Worktrees (each on own line) + containers + kubernetes + teams
+fn format_line4(cfg: &Config, theme: &Theme, data: &StatusData) -> String {
     let r = theme.reset;
     let mut lines: Vec<String> = Vec::new();
 
-    // Worktrees — each gets its own line with name, branch, and path
-    for wt in &data.worktrees.worktrees {
-        let branch_str = wt
-            .branch
-            .as_deref()
-            .map(|b| format!(" {}[{}]{}", theme.cyan, b, r))
-            .unwrap_or_default();
-        lines.push(format!(
-            "{}\u{1f333} {}{} {}{}{}",
-            theme.green, wt.name, r, theme.dim, wt.path, r
-        ) + &branch_str);
+    // Submodules — one line each when details are enabled
+    if cfg.features.show_submodules && cfg.features.show_submodule_details {
+        for sm in &data.submodules.submodules {
+            let (color, state) = if sm.uninitialized {
+                (theme.dim, "uninitialized")
+            } else if sm.out_of_sync {
+                (theme.yellow, "\u{2260} recorded commit")
+            } else if sm.dirty {
+                (theme.yellow, "dirty")
+            } else {
+                (theme.green, "\u{2713}")
+            };
+            let extra = if sm.out_of_sync && sm.dirty { ", dirty" } else { "" };
+            lines.push(format!(
+                "{}\u{1f4e6} {}{} {}{}{} {}{}{}{}",
+                theme.teal, sm.name, r, theme.dim, sm.path, r, color, state, extra, r
+            ));
+        }
+    }
+
+    // Worktrees — each gets its own line with name, branch, path and state,
+    // or a single summary line when there are more than `max_lines`
+    let worktrees = &data.worktrees.worktrees;
+    if worktrees.len() > cfg.worktrees.max_lines {
+        lines.push(format_worktree_summary(theme, worktrees));
+    } else {
+        for wt in worktrees {
+            lines.push(format_worktree(theme, wt));
+        }
     }
 
-    // Docker containers — show all with status and stats
+    // Containers — Compose projects grouped with resource totals, then
+    // standalone containers with their own stats
     if !data.containers.containers.is_empty() {
-        let container_strs: Vec<String> = data
-            .containers
-            .containers
+        let (groups, standalone) = data.containers.grouped();
+        let mut parts: Vec<String> = groups
             .iter()
-            .map(|c| {
-                let color = if c.status == "running" {
-                    theme.green
-                } else if c.status == "exited" {
-                    theme.dim
-                } else {
-                    theme.red
-                };
-                let mut s = format!("{}{}{}", color, c.name, r);
-                // Show status for non-running containers
-                if c.status != "running" {
-                    s.push_str(&format!(" {}{}{}", theme.dim, c.status, r));
-                }
-                // Show stats for running containers
-                if let Some(cpu) = c.cpu_percent {
-                    s.push_str(&format!(" {:.1}%cpu", cpu));
-                }
-                if let Some(ref mem) = c.mem_usage {
-                    s.push_str(&format!(" {}", mem));
-                }
-                s
-            })
+            .map(|g| format_compose_group(theme, g))
             .collect();
-        lines.push(format!("\u{1f433} {}", container_strs.join(" | ")));
+        parts.extend(standalone.iter().map(|c| format_container(theme, c)));
+
+        let (icon, runtime) = match data.containers.runtime {
+            Some(rt) => (rt.icon(), format!("{}{}:{} ", theme.dim, rt.name(), r)),
+            None => ("\u{1f433}", String::new()),
+        };
+        lines.push(format!("{} {}{}", icon, runtime, parts.join(" | ")));
+    }
+
+    // Listening ports — published by containers, or local dev servers
+    if cfg.containers.show_ports {
+        let ports = format_ports(cfg, theme, &data.containers);
+        if !ports.is_empty() {
+            lines.push(ports);
+        }
+    }
+
+    // Kubernetes context — production clusters in red
+    if let Some(ref k) = data.kube {
+        lines.push(format_kube(theme, k));
     }
 
     // Teams
@@ -443,6 +956,205 @@ fn format_line4(theme: &Theme, data: &StatusData) -> String {
     lines.join("\n")
 }
 
+/// One worktree line, e.g. `🌳 feature ~/wt/feature [feat/x] ✗ ↑2 · 3h ago`.
+/// The worktree containing the cwd is marked with `▶` and bolded.
+fn format_worktree(theme: &Theme, wt: &worktrees::Worktree) -> String {
+    let r = theme.reset;
+    let (marker, name_style) = if wt.is_current {
+        ("\u{25b6} ", theme.bold)
+    } else {
+        ("", "")
+    };
+    let main = if wt.is_main { " (main)" } else { "" };
+    let mut s = format!(
+        "{}\u{1f333} {}{}{}{}{} {}{}{}",
+        theme.green, marker, name_style, wt.name, main, r, theme.dim, wt.path, r
+    );
+
+    if let Some(ref branch) = wt.branch {
+        s.push_str(&format!(" {}[{}]{}", theme.cyan, branch, r));
+    }
+    match wt.is_clean {
+        Some(true) => s.push_str(&format!(" {}\u{2713}{}", theme.green, r)),
+        Some(false) => s.push_str(&format!(" {}\u{2717}{}", theme.yellow, r)),
+        None => {}
+    }
+    if let Some((ahead, behind)) = wt.ahead_behind {
+        if ahead > 0 {
+            s.push_str(&format!(" {}\u{2191}{}{}", theme.cyan, ahead, r));
+        }
+        if behind > 0 {
+            s.push_str(&format!(" {}\u{2193}{}{}", theme.orange, behind, r));
+        }
+    }
+    if let Some(time) = wt.last_commit {
+        let secs = (chrono::Local::now() - time).num_seconds();
+        s.push_str(&format!(" {}\u{00b7} {} ago{}", theme.dim, format_age(secs), r));
+    }
+    if let Some(ref reason) = wt.locked {
+        let reason = if reason.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", truncate(reason.trim(), 30))
+        };
+        s.push_str(&format!(" {}\u{1f512}{}{}", theme.orange, reason, r));
+    }
+    if wt.prunable {
+        s.push_str(&format!(" {}prunable{}", theme.red, r));
+    }
+    if let Some(at) = wt.last_session_activity {
+        let secs = (chrono::Local::now() - at).num_seconds();
+        let count = if wt.sessions > 1 {
+            format!("\u{00d7}{} ", wt.sessions)
+        } else {
+            String::new()
+        };
+        s.push_str(&format!(
+            " {}\u{1f916} {}{} ago{}",
+            theme.magenta,
+            count,
+            format_age(secs),
+            r
+        ));
+    }
+
+    s
+}
+
+/// Collapsed worktree list, e.g. `🌳 12 worktrees, 3 dirty, 1 locked, 2 prunable`.
+fn format_worktree_summary(theme: &Theme, worktrees: &[worktrees::Worktree]) -> String {
+    let r = theme.reset;
+    let dirty = worktrees.iter().filter(|w| w.is_clean == Some(false)).count();
+    let locked = worktrees.iter().filter(|w| w.locked.is_some()).count();
+    let prunable = worktrees.iter().filter(|w| w.prunable).count();
+    let active = worktrees.iter().filter(|w| w.sessions > 0).count();
+
+    let mut s = format!("{}\u{1f333} {} worktrees{}", theme.green, worktrees.len(), r);
+    if dirty > 0 {
+        s.push_str(&format!(", {}{} dirty{}", theme.yellow, dirty, r));
+    }
+    if locked > 0 {
+        s.push_str(&format!(", {}{} locked{}", theme.orange, locked, r));
+    }
+    if prunable > 0 {
+        s.push_str(&format!(", {}{} prunable{}", theme.red, prunable, r));
+    }
+    if active > 0 {
+        s.push_str(&format!(", {}\u{1f916} {} active{}", theme.magenta, active, r));
+    }
+    s
+}
+
+/// Ports to open in a browser, e.g. `🔌 :3000 node :8080 web`.
+fn format_ports(cfg: &Config, theme: &Theme, info: &containers::ContainerInfo) -> String {
+    let r = theme.reset;
+    let mut seen = std::collections::HashSet::new();
+    let container_ports = info
+        .containers
+        .iter()
+        .filter(|c| c.status == "running")
+        .flat_map(|c| c.ports.iter().map(move |p| (*p, c.display_name())));
+    let local_ports = info.local_ports.iter().map(|p| (p.port, p.process.as_str()));
+
+    let items: Vec<String> = container_ports
+        .chain(local_ports)
+        .filter(|(port, _)| seen.insert(*port))
+        .map(|(port, owner)| {
+            let label = format!(":{}", port);
+            let label = if cfg.containers.port_links {
+                hyperlink(&format!("http://localhost:{}", port), &label)
+            } else {
+                label
+            };
+            format!("{}{}{} {}{}{}", theme.cyan, label, r, theme.dim, owner, r)
+        })
+        .collect();
+
+    if items.is_empty() {
+        String::new()
+    } else {
+        format!("\u{1f50c} {}", items.join(" "))
+    }
+}
+
+/// Current context and namespace, e.g. `☸ kind-dev:payments`.
+fn format_kube(theme: &Theme, kube: &kube::KubeInfo) -> String {
+    let r = theme.reset;
+    let color = if kube.production {
+        format!("{}{}", theme.red, theme.bold)
+    } else {
+        theme.blue.to_string()
+    };
+    format!(
+        "{}\u{2638} {}{}{}:{}{}",
+        color, kube.context, r, theme.dim, kube.namespace, r
+    )
+}
+
+/// A Compose project, e.g. `myapp: web ✓ db ✓ worker ✗ exit 1 · 3.2%cpu 140MiB`.
+fn format_compose_group(theme: &Theme, group: &containers::ComposeGroup) -> String {
+    let r = theme.reset;
+    let services: Vec<String> = group
+        .containers
+        .iter()
+        .map(|c| format_container_state(theme, c))
+        .collect();
+    let mut s = format!("{}{}:{} {}", theme.bold, group.project, r, services.join(" "));
+
+    let mut totals = Vec::new();
+    if let Some(cpu) = group.cpu_percent {
+        totals.push(format!("{:.1}%cpu", cpu));
+    }
+    if let Some(mem) = group.mem_bytes {
+        totals.push(containers::format_bytes(mem));
+    }
+    if !totals.is_empty() {
+        s.push_str(&format!(" {}\u{00b7}{} {}", theme.dim, r, totals.join(" ")));
+    }
+    s
+}
+
+/// One container with its state and stats, e.g. `web ✓ 1.5%cpu 12MiB / 1GiB`.
+fn format_container(theme: &Theme, c: &containers::Container) -> String {
+    let mut s = format_container_state(theme, c);
+    if let Some(cpu) = c.cpu_percent {
+        s.push_str(&format!(" {:.1}%cpu", cpu));
+    }
+    if let Some(ref mem) = c.mem_usage {
+        s.push_str(&format!(" {}", mem));
+    }
+    s
+}
+
+/// Name and state of a container, e.g. green `web ✓`, red `api ✗ exit 137`
+/// or red `api ✗ unhealthy ↻3`.
+fn format_container_state(theme: &Theme, c: &containers::Container) -> String {
+    let r = theme.reset;
+    let failed_exit = c.exit_code.filter(|code| *code != 0 && c.status == "exited");
+
+    let (color, state) = if let Some(code) = failed_exit {
+        (theme.red, format!("\u{2717} exit {}", code))
+    } else if c.status == "restarting" {
+        (theme.red, "\u{21bb} restarting".to_string())
+    } else if c.health.as_deref() == Some("unhealthy") {
+        (theme.red, "\u{2717} unhealthy".to_string())
+    } else if c.health.as_deref() == Some("starting") {
+        (theme.yellow, "\u{2026} starting".to_string())
+    } else if c.status == "running" {
+        (theme.green, "\u{2713}".to_string())
+    } else {
+        // Exited cleanly, created, paused, ...
+        (theme.dim, c.status.clone())
+    };
+    let mut s = format!("{}{} {}{}", color, c.display_name(), state, r);
+
+    // Crash loops show up as a climbing restart count
+    if let Some(restarts) = c.restart_count.filter(|n| *n > 0) {
+        s.push_str(&format!(" {}\u{21bb}{}{}", theme.orange, restarts, r));
+    }
+    s
+}
+
 /// Shorten a path for display (replace home with ~).
 fn shorten_path(path: &PathBuf) -> String {
     let display = path.display().to_string();
@@ -455,6 +1167,45 @@ fn shorten_path(path: &PathBuf) -> String {
     display
 }
 
+/// Wrap text in an OSC 8 terminal hyperlink.
+fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// Format an age in seconds compactly (e.g. 45s, 12m, 3h, 2d).
+fn format_age(secs: i64) -> String {
+    let secs = secs.max(0);
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else if secs < 86400 {
+        format!("{}h", secs / 3600)
+    } else {
+        format!("{}d", secs / 86400)
+    }
+}
+
+/// Truncate to `max` characters, marking the cut with an ellipsis.
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+    let cut: String = s.chars().take(max.saturating_sub(1)).collect();
+    format!("{}\u{2026}", cut)
+}
+
+/// Format a token count compactly (e.g. 1.2M, 45.3k, 812).
+fn format_tokens(tokens: u64) -> String {
+    if tokens >= 1_000_000 {
+        format!("{:.1}M", tokens as f64 / 1_000_000.0)
+    } else if tokens >= 1_000 {
+        format!("{:.1}k", tokens as f64 / 1_000.0)
+    } else {
+        tokens.to_string()
+    }
+}
+
 /// Get the emoji for a model name.
 fn model_emoji<'a>(model_short: &str, cfg: &'a Config) -> &'a str {
     match model_short {
@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+
+use crate::config::PricingOverride;
+
+/// Token counts for a single API request, or an aggregate of requests.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TokenUsage {
+    pub input: u64,
+    pub output: u64,
+    pub cache_write: u64,
+    pub cache_read: u64,
+}
+
+impl TokenUsage {
+    /// All tokens, including cache reads and writes.
+    pub fn total(&self) -> u64 {
+        self.input + self.output + self.cache_write + self.cache_read
+    }
+
+    /// Tokens sent to the model (everything except output).
+    pub fn prompt(&self) -> u64 {
+        self.input + self.cache_write + self.cache_read
+    }
+
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input += other.input;
+        self.output += other.output;
+        self.cache_write += other.cache_write;
+        self.cache_read += other.cache_read;
+    }
+}
+
+/// Prices in USD per million tokens for one model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+    /// Higher rates applied once a request's prompt exceeds a token threshold.
+    pub long_context: Option<LongContextPricing>,
+}
+
+/// Long-context tier: every token of a request is billed at these rates
+/// when its prompt (input + cache tokens) exceeds `threshold`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LongContextPricing {
+    pub threshold: u64,
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+}
+
+impl ModelPricing {
+    /// Cost in USD of a single request.
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        let (input, output, cache_write, cache_read) = match self.long_context {
+            Some(lc) if usage.prompt() > lc.threshold => {
+                (lc.input, lc.output, lc.cache_write, lc.cache_read)
+            }
+            _ => (self.input, self.output, self.cache_write, self.cache_read),
+        };
+
+        (usage.input as f64 * input
+            + usage.output as f64 * output
+            + usage.cache_write as f64 * cache_write
+            + usage.cache_read as f64 * cache_read)
+            / 1_000_000.0
+    }
+
+    /// Apply the fields set in a `[pricing]` override on top of these prices.
+    fn apply(&mut self, o: &PricingOverride) {
+        self.input = o.input.unwrap_or(self.input);
+        self.output = o.output.unwrap_or(self.output);
+        self.cache_write = o.cache_write.unwrap_or(self.cache_write);
+        self.cache_read = o.cache_read.unwrap_or(self.cache_read);
+
+        let touches_long_context = o.long_context_threshold.is_some()
+            || o.long_context_input.is_some()
+            || o.long_context_output.is_some()
+            || o.long_context_cache_write.is_some()
+            || o.long_context_cache_read.is_some();
+        if !touches_long_context {
+            return;
+        }
+
+        // Without a built-in tier, start from the base rates so a partial
+        // override (e.g. only a threshold) still yields sane prices.
+        let base = self.long_context.unwrap_or(LongContextPricing {
+            threshold: LONG_CONTEXT_THRESHOLD,
+            input: self.input,
+            output: self.output,
+            cache_write: self.cache_write,
+            cache_read: self.cache_read,
+        });
+        self.long_context = Some(LongContextPricing {
+            threshold: o.long_context_threshold.unwrap_or(base.threshold),
+            input: o.long_context_input.unwrap_or(base.input),
+            output: o.long_context_output.unwrap_or(base.output),
+            cache_write: o.long_context_cache_write.unwrap_or(base.cache_write),
+            cache_read: o.long_context_cache_read.unwrap_or(base.cache_read),
+        });
+    }
+}
+
+/// Prompt size above which long-context rates apply.
+const LONG_CONTEXT_THRESHOLD: u64 = 200_000;
+
+const fn price(input: f64, output: f64, cache_write: f64, cache_read: f64) -> ModelPricing {
+    ModelPricing {
+        input,
+        output,
+        cache_write,
+        cache_read,
+        long_context: None,
+    }
+}
+
+/// Sonnet 4.x: 1M context beta, billed at higher rates past 200K tokens.
+const SONNET_4: ModelPricing = ModelPricing {
+    input: 3.0,
+    output: 15.0,
+    cache_write: 3.75,
+    cache_read: 0.30,
+    long_context: Some(LongContextPricing {
+        threshold: LONG_CONTEXT_THRESHOLD,
+        input: 6.0,
+        output: 22.5,
+        cache_write: 7.5,
+        cache_read: 0.60,
+    }),
+};
+
+/// Built-in prices, keyed by a substring of the lowercase model id.
+/// The longest matching pattern wins, so bare family names act as a
+/// fallback for ids released after this table was last updated.
+const BUILTIN_PRICING: &[(&str, ModelPricing)] = &[
+    // Opus
+    ("opus", price(5.0, 25.0, 6.25, 0.50)),
+    ("opus-4-6", price(5.0, 25.0, 6.25, 0.50)),
+    ("opus-4-5", price(5.0, 25.0, 6.25, 0.50)),
+    ("opus-4-1", price(15.0, 75.0, 18.75, 1.50)),
+    ("opus-4-0", price(15.0, 75.0, 18.75, 1.50)),
+    ("opus-4-2025", price(15.0, 75.0, 18.75, 1.50)),
+    ("3-opus", price(15.0, 75.0, 18.75, 1.50)),
+    // Sonnet
+    ("sonnet", SONNET_4),
+    ("sonnet-4", SONNET_4),
+    ("3-7-sonnet", price(3.0, 15.0, 3.75, 0.30)),
+    ("3-5-sonnet", price(3.0, 15.0, 3.75, 0.30)),
+    // Haiku
+    ("haiku", price(1.0, 5.0, 1.25, 0.10)),
+    ("haiku-4-5", price(1.0, 5.0, 1.25, 0.10)),
+    ("3-5-haiku", price(0.80, 4.0, 1.0, 0.08)),
+    ("3-haiku", price(0.25, 1.25, 0.30, 0.03)),
+];
+
+/// Model pricing table: built-in prices merged with `[pricing]` overrides.
+#[derive(Debug, Clone)]
+pub struct PricingTable {
+    entries: Vec<(String, ModelPricing)>,
+}
+
+impl Default for PricingTable {
+    fn default() -> Self {
+        Self {
+            entries: BUILTIN_PRICING
+                .iter()
+                .map(|(pattern, p)| (pattern.to_string(), *p))
+                .collect(),
+        }
+    }
+}
+
+impl PricingTable {
+    /// Build the table from config overrides.
+    ///
+    /// An override for a known pattern only replaces the fields it sets.
+    /// A new pattern inherits unset fields from whatever it would have
+    /// matched before, e.g. `[pricing.opus-5]` starts from the `opus` prices.
+    /// A pattern with nothing to inherit from is skipped unless it sets all
+    /// four base rates, rather than pricing the missing ones at $0.
+    /// Overrides are applied shortest pattern first, so that inheritance
+    /// includes any override of the broader pattern.
+    pub fn new(overrides: &HashMap<String, PricingOverride>) -> Self {
+        let mut table = Self::default();
+
+        let mut sorted: Vec<(String, &PricingOverride)> = overrides
+            .iter()
+            .map(|(pattern, o)| (pattern.to_lowercase(), o))
+            .collect();
+        sorted.sort_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+
+        for (pattern, o) in sorted {
+            if let Some((_, p)) = table.entries.iter_mut().find(|(k, _)| *k == pattern) {
+                p.apply(o);
+                continue;
+            }
+            let mut p = match table.lookup(&pattern) {
+                Some(p) => *p,
+                None => match (o.input, o.output, o.cache_write, o.cache_read) {
+                    (Some(i), Some(out), Some(cw), Some(cr)) => price(i, out, cw, cr),
+                    _ => continue,
+                },
+            };
+            p.apply(o);
+            table.entries.push((pattern, p));
+        }
+
+        table
+    }
+
+    /// Find prices for a model id (e.g. "claude-opus-4-6").
+    pub fn lookup(&self, model_id: &str) -> Option<&ModelPricing> {
+        let lower = model_id.to_lowercase();
+        self.entries
+            .iter()
+            .filter(|(pattern, _)| lower.contains(pattern.as_str()))
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map(|(_, p)| p)
+    }
+
+    /// Cost in USD of a request made with `model_id`, or None for unknown models.
+    pub fn cost(&self, model_id: &str, usage: &TokenUsage) -> Option<f64> {
+        self.lookup(model_id).map(|p| p.cost(usage))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(input: u64, output: u64) -> TokenUsage {
+        TokenUsage {
+            input,
+            output,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn longest_builtin_pattern_wins() {
+        let table = PricingTable::default();
+        assert_eq!(table.lookup("claude-opus-4-1-20250805").unwrap().input, 15.0);
+        assert_eq!(table.lookup("claude-opus-4-6").unwrap().input, 5.0);
+        assert_eq!(table.lookup("claude-3-5-haiku-20241022").unwrap().input, 0.80);
+        // Unreleased ids fall back to the family price
+        assert_eq!(table.lookup("Claude-Opus-9").unwrap().input, 5.0);
+        assert!(table.lookup("gpt-4o").is_none());
+    }
+
+    #[test]
+    fn long_context_rates_apply_past_threshold() {
+        let table = PricingTable::default();
+        let short = table.cost("claude-sonnet-4-5", &usage(100_000, 1_000)).unwrap();
+        let at_threshold = table.cost("claude-sonnet-4-5", &usage(200_000, 0)).unwrap();
+        let long = table.cost("claude-sonnet-4-5", &usage(300_000, 1_000)).unwrap();
+        // $3/M input and $15/M output at or below 200K prompt tokens
+        assert!((short - 0.315).abs() < 1e-9, "{short}");
+        assert!((at_threshold - 0.6).abs() < 1e-9, "{at_threshold}");
+        // $6/M input and $22.50/M output above it
+        assert!((long - 1.8225).abs() < 1e-9, "{long}");
+    }
+
+    #[test]
+    fn override_replaces_only_set_fields() {
+        let overrides = HashMap::from([(
+            "Haiku-4-5".to_string(),
+            PricingOverride {
+                output: Some(6.0),
+                ..Default::default()
+            },
+        )]);
+        let p = *PricingTable::new(&overrides).lookup("claude-haiku-4-5").unwrap();
+        assert_eq!((p.input, p.output, p.cache_read), (1.0, 6.0, 0.10));
+    }
+
+    #[test]
+    fn new_pattern_inherits_overridden_family() {
+        let overrides = HashMap::from([
+            (
+                "opus".to_string(),
+                PricingOverride {
+                    input: Some(7.0),
+                    ..Default::default()
+                },
+            ),
+            (
+                "opus-5".to_string(),
+                PricingOverride {
+                    output: Some(30.0),
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        // HashMap order varies between instances; the result must not
+        for _ in 0..20 {
+            let overrides: HashMap<_, _> = overrides.clone().into_iter().collect();
+            let table = PricingTable::new(&overrides);
+            let p = table.lookup("claude-opus-5").unwrap();
+            assert_eq!((p.input, p.output, p.cache_write), (7.0, 30.0, 6.25));
+        }
+    }
+
+    #[test]
+    fn unknown_pattern_needs_all_base_rates() {
+        let partial = PricingOverride {
+            input: Some(2.0),
+            ..Default::default()
+        };
+        let full = PricingOverride {
+            input: Some(2.0),
+            output: Some(8.0),
+            cache_write: Some(2.5),
+            cache_read: Some(0.2),
+            ..Default::default()
+        };
+        let overrides = HashMap::from([
+            ("mystery".to_string(), partial),
+            ("gpt-5".to_string(), full),
+        ]);
+        let table = PricingTable::new(&overrides);
+        assert!(table.lookup("mystery-model").is_none());
+        let p = table.lookup("gpt-5-mini").unwrap();
+        assert_eq!((p.input, p.output, p.cache_write, p.cache_read), (2.0, 8.0, 2.5, 0.2));
+    }
+}
@@ -1,4 +1,13 @@
+use chrono::{DateTime, TimeZone, Utc};
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::cache::Cache;
+use crate::pricing::{PricingTable, TokenUsage};
 use crate::stdin_data::StdinData;
+use crate::transcript;
 
 /// Session information — model, version, session ID.
 #[derive(Debug, Default)]
@@ -13,6 +22,162 @@ pub struct SessionInfo {
     pub cc_version: String,
     /// Session ID
     pub session_id: Option<String>,
+    /// Tokens and cost per model used in this session, most expensive first
+    pub model_usage: Vec<ModelUsage>,
+}
+
+/// Tokens and cost attributed to one model within the session.
+#[derive(Debug, Clone, Default)]
+pub struct ModelUsage {
+    /// Full model ID (e.g. "claude-sonnet-4-5-20250929")
+    pub model: String,
+    /// Short name (e.g. "sonnet")
+    pub model_short: String,
+    pub tokens: TokenUsage,
+    /// Cost in USD at API prices; 0 for models missing from the pricing table
+    pub cost_usd: f64,
+}
+
+/// A Claude session whose transcript was written to recently.
+#[derive(Debug, Clone)]
+pub struct ActiveSession {
+    /// Working directory recorded in the transcript, if found
+    pub cwd: Option<PathBuf>,
+    /// Transcript project directory name (the cwd with `/` and `.` as `-`)
+    pub project_dir: String,
+    /// Last transcript write
+    pub last_activity: DateTime<Utc>,
+}
+
+impl ActiveSession {
+    /// Whether the session runs inside `dir`. Falls back to comparing the
+    /// encoded project directory when the transcript has no `cwd`.
+    pub fn is_in(&self, dir: &Path) -> bool {
+        match self.cwd {
+            Some(ref cwd) => cwd.starts_with(dir),
+            None => self.project_dir == encode_project_path(dir),
+        }
+    }
+}
+
+/// Sessions with a transcript modified within `within`, across all
+/// `~/.claude/projects/<encoded-path>/` directories.
+/// Cached for 30 seconds, like the active block.
+pub fn active_sessions(within: Duration) -> Vec<ActiveSession> {
+    let cache = Cache::new();
+    let key = format!("active_sessions_{}", within.as_secs());
+    let since = SystemTime::now() - within;
+
+    if let Some(cached) = cache.get(&key, 30) {
+        // Drop sessions that went idle since the scan
+        let since: DateTime<Utc> = since.into();
+        return parse_cached_sessions(&cached)
+            .into_iter()
+            .filter(|s| s.last_activity >= since)
+            .collect();
+    }
+
+    let sessions = scan_sessions(since);
+    cache.set(&key, &serialize_sessions(&sessions));
+    sessions
+}
+
+fn scan_sessions(since: SystemTime) -> Vec<ActiveSession> {
+    let mut sessions = Vec::new();
+
+    for dir in transcript::projects_dirs() {
+        let projects = match std::fs::read_dir(&dir) {
+            Ok(p) => p,
+            Err(_) => continue,
+        };
+        for project in projects.flatten() {
+            let project_dir = project.file_name().to_string_lossy().to_string();
+            let files = match std::fs::read_dir(project.path()) {
+                Ok(f) => f,
+                Err(_) => continue,
+            };
+            // Top-level transcripts only; subagent transcripts live deeper
+            for file in files.flatten() {
+                let path = file.path();
+                if path.extension().map_or(true, |e| e != "jsonl") {
+                    continue;
+                }
+                let modified = match file.metadata().and_then(|m| m.modified()) {
+                    Ok(m) if m >= since => m,
+                    _ => continue,
+                };
+                sessions.push(ActiveSession {
+                    cwd: transcript_cwd(&path),
+                    project_dir: project_dir.clone(),
+                    last_activity: modified.into(),
+                });
+            }
+        }
+    }
+
+    sessions
+}
+
+/// Serialize sessions for the cache, one per line.
+/// Format: last_activity|project_dir|cwd (epoch seconds; cwd empty when unknown)
+fn serialize_sessions(sessions: &[ActiveSession]) -> String {
+    sessions
+        .iter()
+        .map(|s| {
+            let cwd = s.cwd.as_ref().map(|c| c.display().to_string()).unwrap_or_default();
+            format!("{}|{}|{}", s.last_activity.timestamp(), s.project_dir, cwd)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn parse_cached_sessions(cached: &str) -> Vec<ActiveSession> {
+    cached
+        .lines()
+        .filter_map(|line| {
+            // The cwd goes last since it may itself contain `|`
+            let mut parts = line.splitn(3, '|');
+            let at = parts.next()?.parse::<i64>().ok()?;
+            let project_dir = parts.next()?.to_string();
+            let cwd = parts.next()?;
+            Some(ActiveSession {
+                cwd: (!cwd.is_empty()).then(|| PathBuf::from(cwd)),
+                project_dir,
+                last_activity: Utc.timestamp_opt(at, 0).single()?,
+            })
+        })
+        .collect()
+}
+
+/// Claude Code's project directory name for a path: every character other
+/// than ASCII letters and digits becomes `-`.
+pub fn encode_project_path(path: &Path) -> String {
+    path.to_string_lossy()
+        .trim_end_matches('/')
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Most recent `cwd` recorded in a transcript, read from its tail.
+fn transcript_cwd(path: &Path) -> Option<PathBuf> {
+    // Lines carrying tool output can be large; this usually spans several entries
+    const TAIL_BYTES: u64 = 64 * 1024;
+
+    let mut file = std::fs::File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(TAIL_BYTES))).ok()?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf).ok()?;
+
+    String::from_utf8_lossy(&buf)
+        .lines()
+        .rev()
+        .filter(|line| line.contains("\"cwd\""))
+        .find_map(|line| {
+            let json: serde_json::Value = serde_json::from_str(line).ok()?;
+            json.get("cwd")?.as_str().map(PathBuf::from)
+        })
 }
 
 /// Extract session info from stdin data, with env var fallback.
@@ -81,6 +246,146 @@ pub fn collect() -> SessionInfo {
     from_stdin(&StdinData::default())
 }
 
+/// When the current Claude session started: the first timestamp in its
+/// transcript, or else the first time the statusline rendered for this
+/// session id.
+pub fn session_start(data: &StdinData) -> Option<DateTime<Utc>> {
+    if let Some(start) = data.transcript_path.as_deref().and_then(transcript_start) {
+        return Some(start);
+    }
+
+    let session_id = data.session_id.as_deref()?;
+    let cache = Cache::new();
+    let key = format!("session_start_{session_id}");
+    if let Some(ts) = cache
+        .get(&key, 7 * 86400)
+        .and_then(|c| c.trim().parse::<i64>().ok())
+    {
+        return Utc.timestamp_opt(ts, 0).single();
+    }
+
+    let now = Utc::now();
+    cache.set(&key, &now.timestamp().to_string());
+    Some(now)
+}
+
+/// First timestamp found in a transcript's opening lines.
+fn transcript_start(path: &str) -> Option<DateTime<Utc>> {
+    let file = std::fs::File::open(path).ok()?;
+    BufReader::new(file)
+        .lines()
+        .take(50)
+        .map_while(Result::ok)
+        .find_map(|line| {
+            let json: serde_json::Value = serde_json::from_str(&line).ok()?;
+            let ts = json.get("timestamp")?.as_str()?;
+            DateTime::parse_from_rfc3339(ts).ok()
+        })
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Attribute the session transcript's tokens and cost to each model id.
+///
+/// Transcripts only grow, so the aggregate is cached per transcript along
+/// with the byte offset parsed so far, and each render only parses new lines.
+pub fn collect_model_usage(transcript_path: &str, pricing: &PricingTable) -> Vec<ModelUsage> {
+    let cache = Cache::new();
+    let key = format!("model_usage_{transcript_path}");
+
+    let path = Path::new(transcript_path);
+    let (mut usage, mut offset, mut last_key) = cache
+        .get(&key, 86400)
+        .and_then(|c| parse_cached_model_usage(&c))
+        .unwrap_or_default();
+
+    // Transcript was rewritten; the cached totals no longer apply
+    let len = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    if len < offset {
+        usage.clear();
+        offset = 0;
+        last_key = None;
+    }
+
+    let (entries, new_offset) = transcript::read_from_offset(path, offset);
+
+    // The same message is logged once per content block; the previous
+    // chunk's last message may continue at the start of this one.
+    let mut seen: HashSet<String> = last_key.iter().cloned().collect();
+    for entry in &entries {
+        if let Some(ref k) = entry.dedup_key {
+            if !seen.insert(k.clone()) {
+                continue;
+            }
+            last_key = Some(k.clone());
+        }
+
+        let cost = pricing.cost(&entry.model, &entry.usage).unwrap_or(0.0);
+        match usage.iter_mut().find(|m| m.model == entry.model) {
+            Some(m) => {
+                m.tokens.add(&entry.usage);
+                m.cost_usd += cost;
+            }
+            None => usage.push(ModelUsage {
+                model: entry.model.clone(),
+                model_short: shorten_model_name(&entry.model),
+                tokens: entry.usage,
+                cost_usd: cost,
+            }),
+        }
+    }
+
+    if new_offset != offset || !entries.is_empty() {
+        cache.set(&key, &serialize_model_usage(&usage, new_offset, last_key.as_deref()));
+    }
+
+    usage.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+    usage
+}
+
+/// Cache format: first line `offset|last_message_key`, then one line per
+/// model: `model|input|output|cache_write|cache_read|cost`.
+fn serialize_model_usage(usage: &[ModelUsage], offset: u64, last_key: Option<&str>) -> String {
+    let mut lines = vec![format!("{}|{}", offset, last_key.unwrap_or(""))];
+    for m in usage {
+        lines.push(format!(
+            "{}|{}|{}|{}|{}|{}",
+            m.model, m.tokens.input, m.tokens.output, m.tokens.cache_write, m.tokens.cache_read,
+            m.cost_usd
+        ));
+    }
+    lines.join("\n")
+}
+
+type CachedModelUsage = (Vec<ModelUsage>, u64, Option<String>);
+
+fn parse_cached_model_usage(cached: &str) -> Option<CachedModelUsage> {
+    let mut lines = cached.lines();
+    let (offset, last_key) = lines.next()?.split_once('|')?;
+    let offset = offset.parse().ok()?;
+    let last_key = Some(last_key.to_string()).filter(|k| !k.is_empty());
+
+    let mut usage = Vec::new();
+    for line in lines {
+        let parts: Vec<&str> = line.split('|').collect();
+        if parts.len() != 6 {
+            return None;
+        }
+        usage.push(ModelUsage {
+            model: parts[0].to_string(),
+            model_short: shorten_model_name(parts[0]),
+            tokens: TokenUsage {
+                input: parts[1].parse().ok()?,
+                output: parts[2].parse().ok()?,
+                cache_write: parts[3].parse().ok()?,
+                cache_read: parts[4].parse().ok()?,
+            },
+            cost_usd: parts[5].parse().ok()?,
+        });
+    }
+
+    Some((usage, offset, last_key))
+}
+
 fn shorten_model_name(model: &str) -> String {
     let lower = model.to_lowercase();
     if lower.contains("opus") {
@@ -134,3 +439,32 @@ fn extract_model_version(model_id: &str) -> Option<String> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_sessions_round_trip() {
+        let sessions = vec![
+            ActiveSession {
+                cwd: Some(PathBuf::from("/src/odd|name")),
+                project_dir: "-src-odd-name".into(),
+                last_activity: Utc.timestamp_opt(1_760_000_000, 0).unwrap(),
+            },
+            ActiveSession {
+                cwd: None,
+                project_dir: "-src-app".into(),
+                last_activity: Utc.timestamp_opt(1_760_000_060, 0).unwrap(),
+            },
+        ];
+
+        let parsed = parse_cached_sessions(&serialize_sessions(&sessions));
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].cwd.as_deref(), Some(Path::new("/src/odd|name")));
+        assert_eq!(parsed[0].last_activity, sessions[0].last_activity);
+        assert_eq!(parsed[1].cwd, None);
+        assert_eq!(parsed[1].project_dir, "-src-app");
+        assert!(parse_cached_sessions("").is_empty());
+    }
+}
@@ -0,0 +1,415 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::cache::Cache;
+use crate::pricing::TokenUsage;
+
+/// Parsed entries are kept per transcript for this long; nothing looks
+/// back further (see `blocks::active_block`).
+const RETAIN_HOURS: i64 = 24;
+
+/// One assistant message with token usage, parsed from a transcript JSONL line.
+#[derive(Debug, Clone)]
+pub struct UsageEntry {
+    pub timestamp: DateTime<Utc>,
+    /// Model id (e.g. "claude-opus-4-6")
+    pub model: String,
+    pub usage: TokenUsage,
+    /// Message id + request id; the same message is logged once per content block.
+    pub dedup_key: Option<String>,
+}
+
+/// Directories holding Claude Code transcripts (`<dir>/<encoded-path>/<session>.jsonl`).
+/// Honors `CLAUDE_CONFIG_DIR`, then the legacy and XDG locations.
+pub fn projects_dirs() -> Vec<PathBuf> {
+    let mut dirs_found = Vec::new();
+
+    if let Ok(config_dir) = std::env::var("CLAUDE_CONFIG_DIR") {
+        for dir in config_dir.split(',').filter(|d| !d.trim().is_empty()) {
+            dirs_found.push(PathBuf::from(dir.trim()).join("projects"));
+        }
+    }
+
+    if let Some(home) = dirs::home_dir() {
+        dirs_found.push(home.join(".claude/projects"));
+        dirs_found.push(home.join(".config/claude/projects"));
+    }
+
+    dirs_found.retain(|d| d.is_dir());
+    dirs_found.dedup();
+    dirs_found
+}
+
+/// Find transcript files modified at or after `since`.
+pub fn recent_files(since: SystemTime) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for dir in projects_dirs() {
+        // <projects>/<encoded-path>/<session>.jsonl, plus subagent transcripts
+        // nested one or two levels deeper.
+        find_jsonl(&dir, since, 3, &mut files);
+    }
+    files
+}
+
+fn find_jsonl(dir: &Path, since: SystemTime, depth: u8, out: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(e) => e,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let meta = match entry.metadata() {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+
+        if meta.is_dir() {
+            if depth > 0 {
+                find_jsonl(&path, since, depth - 1, out);
+            }
+        } else if path.extension().is_some_and(|e| e == "jsonl") {
+            let fresh = meta.modified().map(|m| m >= since).unwrap_or(false);
+            if fresh {
+                out.push(path);
+            }
+        }
+    }
+}
+
+/// Parse usage entries appended to a transcript since byte `offset`.
+/// Returns the entries and the offset to resume from; a trailing partial
+/// line (still being written) is left for the next call.
+pub fn read_from_offset(path: &Path, offset: u64) -> (Vec<UsageEntry>, u64) {
+    let mut file = match std::fs::File::open(path) {
+        Ok(f) => f,
+        Err(_) => return (Vec::new(), offset),
+    };
+
+    // File was truncated or replaced: start over
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let offset = if len < offset { 0 } else { offset };
+
+    let mut buf = Vec::new();
+    if file.seek(SeekFrom::Start(offset)).is_err() || file.read_to_end(&mut buf).is_err() {
+        return (Vec::new(), offset);
+    }
+
+    let complete = match buf.iter().rposition(|&b| b == b'\n') {
+        Some(pos) => pos + 1,
+        None => return (Vec::new(), offset),
+    };
+
+    let entries = String::from_utf8_lossy(&buf[..complete])
+        .lines()
+        .filter_map(parse_line)
+        .collect();
+
+    (entries, offset + complete as u64)
+}
+
+/// Parse usage entries from all recently modified transcripts, deduplicated
+/// and sorted by timestamp.
+pub fn recent_entries(since: DateTime<Utc>) -> Vec<UsageEntry> {
+    let cache = Cache::new();
+    let retain_since = Utc::now() - Duration::hours(RETAIN_HOURS);
+
+    let mut entries: Vec<UsageEntry> = recent_files(since.into())
+        .iter()
+        .flat_map(|path| cached_file_entries(&cache, path, retain_since))
+        .filter(|e| e.timestamp >= since)
+        .collect();
+
+    dedup_and_sort(&mut entries);
+    entries
+}
+
+/// Entries of one transcript from the last `RETAIN_HOURS`.
+///
+/// Like `session::collect_model_usage`, the parsed entries are cached with
+/// the byte offset read so far, so a long session only parses new lines.
+fn cached_file_entries(
+    cache: &Cache,
+    path: &Path,
+    retain_since: DateTime<Utc>,
+) -> Vec<UsageEntry> {
+    let key = format!("transcript_entries_{}", path.display());
+    let (entries, offset) = cache
+        .get(&key, 86400)
+        .and_then(|c| parse_cached_entries(&c))
+        .unwrap_or_default();
+
+    let (entries, new_offset, changed) = refresh_entries(path, entries, offset, retain_since);
+    if changed {
+        cache.set(&key, &serialize_entries(&entries, new_offset));
+    }
+    entries
+}
+
+/// Append entries written since `offset` and drop those before
+/// `retain_since`. Returns the entries, the new offset and whether either
+/// changed.
+fn refresh_entries(
+    path: &Path,
+    mut entries: Vec<UsageEntry>,
+    offset: u64,
+    retain_since: DateTime<Utc>,
+) -> (Vec<UsageEntry>, u64, bool) {
+    // Transcript was rewritten; the cached entries no longer apply
+    let len = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let mut changed = false;
+    let offset = if len < offset {
+        entries.clear();
+        changed = true;
+        0
+    } else {
+        offset
+    };
+
+    let (new, new_offset) = read_from_offset(path, offset);
+    let before = entries.len() + new.len();
+    entries.extend(new);
+    entries.retain(|e| e.timestamp >= retain_since);
+
+    changed |= new_offset != offset || entries.len() != before;
+    (entries, new_offset, changed)
+}
+
+/// Cache format: first line the offset, then one line per entry:
+/// `timestamp_ms|model|input|output|cache_write|cache_read|dedup_key`.
+fn serialize_entries(entries: &[UsageEntry], offset: u64) -> String {
+    let mut lines = vec![offset.to_string()];
+    for e in entries {
+        lines.push(format!(
+            "{}|{}|{}|{}|{}|{}|{}",
+            e.timestamp.timestamp_millis(),
+            e.model,
+            e.usage.input,
+            e.usage.output,
+            e.usage.cache_write,
+            e.usage.cache_read,
+            e.dedup_key.as_deref().unwrap_or("")
+        ));
+    }
+    lines.join("\n")
+}
+
+fn parse_cached_entries(cached: &str) -> Option<(Vec<UsageEntry>, u64)> {
+    let mut lines = cached.lines();
+    let offset = lines.next()?.parse().ok()?;
+
+    let mut entries = Vec::new();
+    for line in lines {
+        let parts: Vec<&str> = line.splitn(7, '|').collect();
+        if parts.len() != 7 {
+            return None;
+        }
+        entries.push(UsageEntry {
+            timestamp: Utc.timestamp_millis_opt(parts[0].parse().ok()?).single()?,
+            model: parts[1].to_string(),
+            usage: TokenUsage {
+                input: parts[2].parse().ok()?,
+                output: parts[3].parse().ok()?,
+                cache_write: parts[4].parse().ok()?,
+                cache_read: parts[5].parse().ok()?,
+            },
+            dedup_key: Some(parts[6].to_string()).filter(|k| !k.is_empty()),
+        });
+    }
+
+    Some((entries, offset))
+}
+
+/// Drop repeated messages (same message and request id) and sort by time.
+pub fn dedup_and_sort(entries: &mut Vec<UsageEntry>) {
+    let mut seen = HashSet::new();
+    entries.retain(|e| match e.dedup_key {
+        Some(ref key) => seen.insert(key.clone()),
+        None => true,
+    });
+    entries.sort_by_key(|e| e.timestamp);
+}
+
+/// Parse a single transcript line. Only assistant messages carrying usage
+/// data produce an entry.
+pub fn parse_line(line: &str) -> Option<UsageEntry> {
+    // Cheap pre-filter: most lines are tool results and user messages
+    if !line.contains("\"usage\"") {
+        return None;
+    }
+
+    let json: Value = serde_json::from_str(line).ok()?;
+    let message = json.get("message")?;
+    let usage = message.get("usage")?;
+
+    let timestamp = json
+        .get("timestamp")
+        .and_then(|v| v.as_str())
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())?
+        .with_timezone(&Utc);
+
+    let model = message
+        .get("model")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown")
+        .to_string();
+
+    // Placeholder model used for locally generated messages (errors, etc.)
+    if model == "<synthetic>" {
+        return None;
+    }
+
+    let tokens = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    let usage = TokenUsage {
+        input: tokens("input_tokens"),
+        output: tokens("output_tokens"),
+        cache_write: tokens("cache_creation_input_tokens"),
+        cache_read: tokens("cache_read_input_tokens"),
+    };
+
+    let message_id = message.get("id").and_then(|v| v.as_str());
+    let request_id = json.get("requestId").and_then(|v| v.as_str());
+    let dedup_key = match (message_id, request_id) {
+        (Some(m), Some(r)) => Some(format!("{m}:{r}")),
+        (Some(m), None) => Some(m.to_string()),
+        _ => None,
+    };
+
+    Some(UsageEntry {
+        timestamp,
+        model,
+        usage,
+        dedup_key,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINE: &str = r#"{"type":"assistant","timestamp":"2026-03-02T10:47:13.512Z","requestId":"req_1","message":{"id":"msg_1","model":"claude-opus-4-6","usage":{"input_tokens":10,"output_tokens":20,"cache_creation_input_tokens":30,"cache_read_input_tokens":40}}}"#;
+
+    #[test]
+    fn parses_assistant_usage_line() {
+        let e = parse_line(LINE).unwrap();
+        assert_eq!(e.timestamp.to_rfc3339(), "2026-03-02T10:47:13.512+00:00");
+        assert_eq!(e.model, "claude-opus-4-6");
+        assert_eq!(
+            e.usage,
+            TokenUsage {
+                input: 10,
+                output: 20,
+                cache_write: 30,
+                cache_read: 40
+            }
+        );
+        assert_eq!(e.dedup_key.as_deref(), Some("msg_1:req_1"));
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        assert!(parse_line("").is_none());
+        assert!(parse_line(r#"{"type":"user","message":{"content":"hi"}}"#).is_none());
+        assert!(parse_line(&LINE[..LINE.len() - 2]).is_none());
+        assert!(parse_line(&LINE.replace("2026-03-02T10:47:13.512Z", "yesterday")).is_none());
+        assert!(
+            parse_line(&LINE.replace(r#""timestamp":"2026-03-02T10:47:13.512Z","#, "")).is_none()
+        );
+        assert!(parse_line(&LINE.replace("claude-opus-4-6", "<synthetic>")).is_none());
+    }
+
+    #[test]
+    fn dedups_by_message_and_request_id() {
+        let later = LINE.replace("10:47:13.512Z", "10:50:00Z");
+        let retried = later.replace("req_1", "req_2");
+        let earlier = LINE
+            .replace("10:47:13.512Z", "10:40:00Z")
+            .replace(r#""requestId":"req_1","#, "")
+            .replace("msg_1", "msg_0");
+        let mut entries: Vec<UsageEntry> =
+            [LINE, later.as_str(), retried.as_str(), earlier.as_str()]
+                .iter()
+                .filter_map(|l| parse_line(l))
+                .collect();
+        dedup_and_sort(&mut entries);
+
+        let keys: Vec<_> = entries
+            .iter()
+            .map(|e| e.dedup_key.as_deref().unwrap())
+            .collect();
+        // The second `msg_1:req_1` copy is dropped; a new request id is kept
+        assert_eq!(keys, ["msg_0", "msg_1:req_1", "msg_1:req_2"]);
+    }
+
+    #[test]
+    fn refreshes_entries_from_the_last_offset() {
+        let path = std::env::temp_dir().join(format!(
+            "statusline-test-transcript-{}.jsonl",
+            std::process::id()
+        ));
+        let old = LINE
+            .replace("2026-03-02", "2026-02-20")
+            .replace("msg_1", "msg_old");
+        let second = LINE
+            .replace("10:47:13.512Z", "11:00:00Z")
+            .replace("msg_1", "msg_2");
+        let retain_since = DateTime::parse_from_rfc3339("2026-03-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        std::fs::write(&path, format!("{old}\n{LINE}\n")).unwrap();
+        let (entries, offset, changed) = refresh_entries(&path, Vec::new(), 0, retain_since);
+        assert!(changed);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(offset, (old.len() + LINE.len() + 2) as u64);
+
+        // Only the complete new line is read; the partial one waits
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        std::io::Write::write_all(&mut file, format!("{second}\n{{\"partial").as_bytes()).unwrap();
+        let (entries, next, changed) = refresh_entries(&path, entries, offset, retain_since);
+        assert!(changed);
+        assert_eq!(next, offset + second.len() as u64 + 1);
+        let keys: Vec<_> = entries
+            .iter()
+            .map(|e| e.dedup_key.as_deref().unwrap())
+            .collect();
+        assert_eq!(keys, ["msg_1:req_1", "msg_2:req_1"]);
+
+        let (entries, same, changed) = refresh_entries(&path, entries, next, retain_since);
+        assert_eq!((entries.len(), same, changed), (2, next, false));
+
+        // A rewritten, shorter transcript starts over
+        std::fs::write(&path, format!("{second}\n")).unwrap();
+        let (entries, _, changed) = refresh_entries(&path, entries, next, retain_since);
+        assert!(changed);
+        assert_eq!(entries.len(), 1);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn round_trips_cached_entries() {
+        let mut no_key = parse_line(LINE).unwrap();
+        no_key.dedup_key = None;
+        let entries = vec![parse_line(LINE).unwrap(), no_key];
+
+        let (parsed, offset) = parse_cached_entries(&serialize_entries(&entries, 4096)).unwrap();
+        assert_eq!(offset, 4096);
+        assert_eq!(parsed.len(), 2);
+        for (a, b) in parsed.iter().zip(&entries) {
+            assert_eq!(
+                (a.timestamp, &a.model, a.usage),
+                (b.timestamp, &b.model, b.usage)
+            );
+            assert_eq!(a.dedup_key, b.dedup_key);
+        }
+        assert!(parse_cached_entries("4096\nnot|an|entry").is_none());
+    }
+}
@@ -0,0 +1,289 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Timelike, Utc};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::usage_limits::UsageLimitsInfo;
+
+/// History older than this is pruned.
+const RETENTION_DAYS: i64 = 30;
+
+/// Rewrite the file to drop old readings once it grows past this size.
+const PRUNE_SIZE_BYTES: u64 = 512 * 1024;
+
+/// Identical consecutive readings closer than this are not recorded again.
+const DEDUP_SECS: i64 = 300;
+
+const SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One usage reading for both limit windows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageSample {
+    pub at: DateTime<Utc>,
+    pub five_hour_percent: Option<f64>,
+    pub five_hour_resets_at: Option<DateTime<Utc>>,
+    pub seven_day_percent: Option<f64>,
+    pub seven_day_resets_at: Option<DateTime<Utc>>,
+}
+
+impl UsageSample {
+    fn same_reading(&self, other: &UsageSample) -> bool {
+        self.five_hour_percent == other.five_hour_percent
+            && self.five_hour_resets_at == other.five_hour_resets_at
+            && self.seven_day_percent == other.seven_day_percent
+            && self.seven_day_resets_at == other.seven_day_resets_at
+    }
+}
+
+/// History file: one tab-separated line per reading.
+/// Format: at, 5h%, 5h reset, 7d%, 7d reset (epoch seconds; empty when unknown)
+fn history_path() -> PathBuf {
+    dirs::data_local_dir()
+        .or_else(|| dirs::home_dir().map(|h| h.join(".local/share")))
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("statusline-rs/usage_history.tsv")
+}
+
+/// Append a successful usage reading to the history.
+pub fn record(info: &UsageLimitsInfo) {
+    if info.five_hour_percent.is_none() && info.seven_day_percent.is_none() {
+        return;
+    }
+
+    let sample = UsageSample {
+        at: Utc::now(),
+        five_hour_percent: info.five_hour_percent,
+        five_hour_resets_at: info.five_hour_resets_at.map(|t| t.with_timezone(&Utc)),
+        seven_day_percent: info.seven_day_percent,
+        seven_day_resets_at: info.seven_day_resets_at.map(|t| t.with_timezone(&Utc)),
+    };
+
+    let path = history_path();
+    if let Some(last) = load_from(&path).last() {
+        if last.same_reading(&sample) && (sample.at - last.at).num_seconds() < DEDUP_SECS {
+            return;
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(mut file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+    {
+        let _ = writeln!(file, "{}", serialize(&sample));
+    }
+
+    prune(&path);
+}
+
+/// Load all readings at or after `since`, oldest first.
+pub fn load(since: DateTime<Utc>) -> Vec<UsageSample> {
+    load_from(&history_path())
+        .into_iter()
+        .filter(|s| s.at >= since)
+        .collect()
+}
+
+fn load_from(path: &Path) -> Vec<UsageSample> {
+    std::fs::read_to_string(path)
+        .map(|content| content.lines().filter_map(parse).collect())
+        .unwrap_or_default()
+}
+
+/// Drop readings past the retention period once the file gets large.
+fn prune(path: &Path) {
+    let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    if size < PRUNE_SIZE_BYTES {
+        return;
+    }
+
+    let cutoff = Utc::now() - Duration::days(RETENTION_DAYS);
+    let kept: Vec<String> = load_from(path)
+        .iter()
+        .filter(|s| s.at >= cutoff)
+        .map(serialize)
+        .collect();
+    let _ = std::fs::write(path, kept.join("\n") + "\n");
+}
+
+fn serialize(s: &UsageSample) -> String {
+    let pct = |p: Option<f64>| p.map(|v| v.to_string()).unwrap_or_default();
+    let ts = |t: Option<DateTime<Utc>>| t.map(|v| v.timestamp().to_string()).unwrap_or_default();
+    format!(
+        "{}\t{}\t{}\t{}\t{}",
+        s.at.timestamp(),
+        pct(s.five_hour_percent),
+        ts(s.five_hour_resets_at),
+        pct(s.seven_day_percent),
+        ts(s.seven_day_resets_at)
+    )
+}
+
+fn parse(line: &str) -> Option<UsageSample> {
+    let parts: Vec<&str> = line.split('\t').collect();
+    if parts.len() != 5 {
+        return None;
+    }
+
+    let ts = |s: &str| s.parse::<i64>().ok().and_then(|t| Utc.timestamp_opt(t, 0).single());
+    let pct = |s: &str| s.parse::<f64>().ok();
+
+    Some(UsageSample {
+        at: ts(parts[0])?,
+        five_hour_percent: pct(parts[1]),
+        five_hour_resets_at: ts(parts[2]),
+        seven_day_percent: pct(parts[3]),
+        seven_day_resets_at: ts(parts[4]),
+    })
+}
+
+// ============================================================================
+// `statusline usage history`
+// ============================================================================
+
+/// Print the last `days` of history as a daily table, or as one hourly
+/// sparkline per day and window.
+pub fn print_history(days: u32, sparkline: bool) {
+    let samples = load(range_start(days));
+    if samples.is_empty() {
+        println!("No usage history recorded yet ({})", history_path().display());
+        return;
+    }
+
+    let mut by_day: BTreeMap<NaiveDate, Vec<&UsageSample>> = BTreeMap::new();
+    for s in &samples {
+        by_day
+            .entry(s.at.with_timezone(&Local).date_naive())
+            .or_default()
+            .push(s);
+    }
+
+    if sparkline {
+        print_sparklines(&by_day);
+    } else {
+        print_table(&by_day);
+    }
+}
+
+/// Local midnight starting the last `days` days, today included. A range
+/// reaching past the dates chrono can represent covers every sample.
+fn range_start(days: u32) -> DateTime<Utc> {
+    Local::now()
+        .date_naive()
+        .checked_sub_signed(Duration::days(days.saturating_sub(1) as i64))
+        .map(|since| {
+            Local
+                .from_local_datetime(&since.and_hms_opt(0, 0, 0).unwrap_or_default())
+                .earliest()
+                .map(|t| t.with_timezone(&Utc))
+                .unwrap_or_else(|| Utc::now() - Duration::days(days as i64))
+        })
+        .unwrap_or(DateTime::<Utc>::MIN_UTC)
+}
+
+fn print_table(by_day: &BTreeMap<NaiveDate, Vec<&UsageSample>>) {
+    println!(
+        "{:<10}  {:>7}  {:>9}  {:>6}  {:>6}",
+        "Day", "5H peak", "5H blocks", "7D", "7D +/-"
+    );
+
+    for (day, samples) in by_day {
+        let five_peak = samples
+            .iter()
+            .filter_map(|s| s.five_hour_percent)
+            .fold(None, |acc: Option<f64>, p| Some(acc.map_or(p, |a| a.max(p))));
+
+        let mut blocks: Vec<i64> = samples
+            .iter()
+            .filter_map(|s| s.five_hour_resets_at.map(|t| t.timestamp() / 60))
+            .collect();
+        blocks.dedup();
+
+        let seven: Vec<f64> = samples.iter().filter_map(|s| s.seven_day_percent).collect();
+        let seven_last = seven.last().copied();
+        // Growth within the day; a weekly reset in between makes this negative,
+        // in which case the day's consumption is just the closing value.
+        let seven_delta = match (seven.first(), seven.last()) {
+            (Some(first), Some(last)) if last >= first => Some(last - first),
+            (Some(_), Some(last)) => Some(*last),
+            _ => None,
+        };
+
+        let fmt = |p: Option<f64>| p.map(|v| format!("{v:.0}%")).unwrap_or_else(|| "-".into());
+        println!(
+            "{:<10}  {:>7}  {:>9}  {:>6}  {:>6}",
+            day.format("%a %m-%d"),
+            fmt(five_peak),
+            blocks.len(),
+            fmt(seven_last),
+            seven_delta
+                .map(|d| format!("+{d:.0}%"))
+                .unwrap_or_else(|| "-".into())
+        );
+    }
+}
+
+/// Extracts one window's utilization from a reading.
+type SampleValue = fn(&UsageSample) -> Option<f64>;
+
+fn print_sparklines(by_day: &BTreeMap<NaiveDate, Vec<&UsageSample>>) {
+    let windows: [(&str, SampleValue); 2] = [
+        ("5-hour utilization (hourly peak)", |s| s.five_hour_percent),
+        ("7-day utilization (hourly peak)", |s| s.seven_day_percent),
+    ];
+
+    for (i, (title, value)) in windows.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{title}");
+
+        for (day, samples) in by_day {
+            let mut hours: [Option<f64>; 24] = [None; 24];
+            for s in samples {
+                if let Some(p) = value(s) {
+                    let h = s.at.with_timezone(&Local).hour() as usize;
+                    hours[h] = Some(hours[h].map_or(p, |v: f64| v.max(p)));
+                }
+            }
+
+            let line: String = hours.iter().map(|h| spark_char(*h)).collect();
+            let peak = hours.iter().flatten().fold(0.0_f64, |a, b| a.max(*b));
+            println!("{}  {}  {:>3.0}%", day.format("%a %m-%d"), line, peak);
+        }
+    }
+}
+
+fn spark_char(percent: Option<f64>) -> char {
+    match percent {
+        None => ' ',
+        Some(p) => {
+            let idx = ((p.clamp(0.0, 100.0) / 100.0) * (SPARK_CHARS.len() - 1) as f64).round();
+            SPARK_CHARS[idx as usize]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range_starts_at_local_midnight() {
+        let today = range_start(1).with_timezone(&Local);
+        assert_eq!(today.date_naive(), Local::now().date_naive());
+        assert_eq!((today.hour(), today.minute()), (0, 0));
+        assert_eq!(range_start(0), range_start(1));
+        let week = range_start(8).with_timezone(&Local).date_naive();
+        assert_eq!((today.date_naive() - week).num_days(), 7);
+    }
+
+    #[test]
+    fn huge_ranges_cover_all_history() {
+        assert_eq!(range_start(200_000_000), DateTime::<Utc>::MIN_UTC);
+        assert_eq!(range_start(u32::MAX), DateTime::<Utc>::MIN_UTC);
+    }
+}
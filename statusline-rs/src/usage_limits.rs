@@ -2,7 +2,10 @@ use chrono::{Local, DateTime, Timelike};
 use serde_json::Value;
 use std::process::Command;
 
+use crate::blocks;
 use crate::cache::Cache;
+use crate::pricing::PricingTable;
+use crate::usage_history;
 
 /// Usage limit information for 5-hour and 7-day windows.
 #[derive(Debug, Default)]
@@ -13,16 +16,24 @@ pub struct UsageLimitsInfo {
     pub five_hour_remaining: Option<String>,
     /// 5-hour usage percentage (0-100)
     pub five_hour_percent: Option<f64>,
+    /// When the 5-hour block resets
+    pub five_hour_resets_at: Option<DateTime<Local>>,
+    /// Tokens consumed in the current 5-hour block (transcript fallback only)
+    pub five_hour_tokens: Option<u64>,
+    /// Cost in USD of the current 5-hour block (transcript fallback only)
+    pub five_hour_cost: Option<f64>,
 
     /// 7-day reset time display (e.g., "Wed 9:59 PM")
     pub seven_day_reset: Option<String>,
     /// 7-day usage percentage (0-100)
     pub seven_day_percent: Option<f64>,
+    /// When the 7-day window resets
+    pub seven_day_resets_at: Option<DateTime<Local>>,
 }
 
 /// Collect usage limit information.
-/// Priority: OAuth API (cached) → local files → time-based estimates.
-pub fn collect() -> UsageLimitsInfo {
+/// Priority: OAuth API (cached) → local files → blocks reconstructed from transcripts.
+pub fn collect(pricing: &PricingTable) -> UsageLimitsInfo {
     let cache = Cache::new();
 
     // Try cached OAuth API response first (5 min TTL)
@@ -45,8 +56,8 @@ pub fn collect() -> UsageLimitsInfo {
         return info;
     }
 
-    // Last resort: time-based estimates (no percentage data)
-    estimate_from_time()
+    // Last resort: reconstruct the block from transcripts (no percentage data)
+    estimate_from_transcripts(pricing)
 }
 
 /// Fetch usage data from Anthropic OAuth API.
@@ -83,6 +94,7 @@ fn fetch_from_oauth_api(cache: &Cache) -> Option<UsageLimitsInfo> {
 
     let info = parse_api_response(&json);
     if info.five_hour_percent.is_some() {
+        usage_history::record(&info);
         Some(info)
     } else {
         None
@@ -101,6 +113,7 @@ fn parse_api_response(json: &Value) -> UsageLimitsInfo {
             .map(|v| v.round());
 
         if let Some(resets_at) = five_hour.get("resets_at").and_then(|v| v.as_str()) {
+            info.five_hour_resets_at = parse_reset_time(resets_at);
             let (clock, remaining) = format_reset_times(resets_at);
             info.five_hour_reset = clock;
             info.five_hour_remaining = remaining;
@@ -115,6 +128,7 @@ fn parse_api_response(json: &Value) -> UsageLimitsInfo {
             .map(|v| v.round());
 
         if let Some(resets_at) = seven_day.get("resets_at").and_then(|v| v.as_str()) {
+            info.seven_day_resets_at = parse_reset_time(resets_at);
             let (display, _) = format_reset_times(resets_at);
             info.seven_day_reset = display;
         }
@@ -158,6 +172,14 @@ fn get_oauth_token() -> Option<String> {
 /// Format ISO timestamp into clock time and remaining time.
 /// Returns (clock_display, remaining_display).
 fn format_reset_times(iso_timestamp: &str) -> (Option<String>, Option<String>) {
+    match parse_reset_time(iso_timestamp) {
+        Some(dt) => format_reset_datetime(dt),
+        None => (None, None),
+    }
+}
+
+/// Parse an ISO reset timestamp, tolerating missing timezone and fractional seconds.
+fn parse_reset_time(iso_timestamp: &str) -> Option<DateTime<Local>> {
     let reset_dt = DateTime::parse_from_rfc3339(iso_timestamp)
         .or_else(|_| {
             // Try without fractional seconds
@@ -171,11 +193,11 @@ fn format_reset_times(iso_timestamp: &str) -> (Option<String>, Option<String>) {
         })
         .ok();
 
-    let reset_dt = match reset_dt {
-        Some(dt) => dt.with_timezone(&Local),
-        None => return (None, None),
-    };
+    reset_dt.map(|dt| dt.with_timezone(&Local))
+}
 
+/// Format a reset time into clock time and remaining time.
+fn format_reset_datetime(reset_dt: DateTime<Local>) -> (Option<String>, Option<String>) {
     let now = Local::now();
     let diff = reset_dt.signed_duration_since(now);
     let total_secs = diff.num_seconds();
@@ -244,6 +266,7 @@ fn try_local_files() -> Option<UsageLimitsInfo> {
                         .map(|s| s.to_string());
                 }
                 if info.five_hour_percent.is_some() {
+                    usage_history::record(&info);
                     return Some(info);
                 }
             }
@@ -253,23 +276,18 @@ fn try_local_files() -> Option<UsageLimitsInfo> {
     None
 }
 
-/// Last resort: estimate reset times from current time (no usage % available).
-fn estimate_from_time() -> UsageLimitsInfo {
+/// Last resort: reconstruct the active 5-hour block from local transcripts.
+/// Blocks start at the first message after the previous one expired, so the
+/// reset time is accurate even without the API (no usage % available).
+fn estimate_from_transcripts(pricing: &PricingTable) -> UsageLimitsInfo {
     let mut info = UsageLimitsInfo::default();
-    let now = Local::now();
-    let current_hour = now.hour() as i64;
-    let block_end_hour = ((current_hour / 5) + 1) * 5;
-
-    let hours_remaining = block_end_hour - current_hour;
-    let mins_remaining = 60 - now.minute() as i64;
-
-    if hours_remaining > 0 {
-        let reset_hour = (block_end_hour % 24) as u32;
-        info.five_hour_reset = Some(format!("{:02}:{:02}", reset_hour, 0));
 
-        let h = if mins_remaining == 60 { hours_remaining } else { hours_remaining - 1 };
-        let m = if mins_remaining == 60 { 0 } else { mins_remaining };
-        info.five_hour_remaining = Some(format!("{}h{}m", h, m));
+    if let Some(block) = blocks::active_block(pricing) {
+        let (clock, remaining) = format_reset_datetime(block.end.with_timezone(&Local));
+        info.five_hour_reset = clock;
+        info.five_hour_remaining = remaining;
+        info.five_hour_tokens = Some(block.tokens);
+        info.five_hour_cost = Some(block.cost_usd);
     }
 
     info
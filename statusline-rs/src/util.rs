@@ -0,0 +1,70 @@
+/// Match `text` against a glob pattern where `*` matches any run of
+/// characters (including `/`) and `?` matches exactly one.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    // Position of the last `*` and the text index it was tried at
+    let mut star: Option<(usize, usize)> = None;
+
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((sp, st)) = star {
+            // Let the last `*` absorb one more character and retry
+            pi = sp + 1;
+            ti = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+/// Percent-encode `text` for use in a URL path, keeping `/` as a separator.
+pub fn percent_encode_path(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for b in text.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{b:02X}")),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn star_matches_any_run_including_slashes() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("/home/*/big-repo", "/home/me/src/big-repo"));
+        assert!(glob_match("*prod*", "gke_acme_prod-eu"));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("a*b*c", "aXbYbZ"));
+    }
+
+    #[test]
+    fn question_mark_matches_exactly_one() {
+        assert!(glob_match("redis-?", "redis-1"));
+        assert!(!glob_match("redis-?", "redis-"));
+        assert!(!glob_match("redis-?", "redis-10"));
+    }
+
+    #[test]
+    fn literal_patterns_match_whole_text() {
+        assert!(glob_match("postgres:16", "postgres:16"));
+        assert!(!glob_match("postgres", "postgres:16"));
+        assert!(!glob_match("", "x"));
+    }
+}
@@ -1,5 +1,11 @@
-use git2::Repository;
-use std::path::Path;
+use chrono::{DateTime, Local, TimeZone};
+use git2::{Repository, WorktreeLockStatus};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::config::{GitStatusConfig, WorktreesConfig};
+use crate::git;
+use crate::session::{self, ActiveSession};
 
 /// Git worktree information.
 #[derive(Debug, Default)]
@@ -14,64 +20,164 @@ pub struct Worktree {
     pub branch: Option<String>, // e.g., "feat/container-mcp-integration"
     pub path: String,           // e.g., "~/Desktop/Claude-WorkOnMac/worktrees/mconnect-mcp-integration"
     pub is_main: bool,          // true if this is the main worktree (not a linked worktree)
+    /// The worktree containing `cwd`
+    pub is_current: bool,
+    /// None when status is skipped for this path
+    pub is_clean: Option<bool>,
+    /// Commits ahead of and behind the upstream, when the branch tracks one
+    pub ahead_behind: Option<(usize, usize)>,
+    /// When the HEAD commit was made
+    pub last_commit: Option<DateTime<Local>>,
+    /// Locked with `git worktree lock`; the reason, if one was given
+    pub locked: Option<String>,
+    /// Would be removed by `git worktree prune` (its directory is gone)
+    pub prunable: bool,
+    /// Number of Claude sessions currently working in this worktree
+    pub sessions: usize,
+    /// Most recent transcript write among those sessions
+    pub last_session_activity: Option<DateTime<Local>>,
 }
 
 /// List git worktrees for the repository at `cwd`.
 ///
-/// This function collects information about all linked worktrees in the repository.
-/// Note: The main worktree is NOT included in this list, as it's already displayed
-/// in the primary statusline. This only returns linked worktrees created via
-/// `git worktree add`.
-pub fn collect(cwd: &Path) -> WorktreeInfo {
+/// This function collects information about all linked worktrees in the repository,
+/// each with its status, ahead/behind and last commit age. The main worktree is
+/// only included when `cwd` is inside a linked worktree; otherwise it's already
+/// displayed in the primary statusline.
+///
+/// Status goes through the same cache and time budget as the primary
+/// statusline, with all worktrees checked in parallel.
+pub fn collect(
+    cwd: &Path,
+    cfg: &GitStatusConfig,
+    wt_cfg: &WorktreesConfig,
+    status_ttl: u64,
+) -> WorktreeInfo {
     let mut info = WorktreeInfo::default();
 
     let repo = match Repository::discover(cwd) {
         Ok(r) => r,
         Err(_) => return info,
     };
+    let current = repo.workdir().map(|p| p.to_path_buf());
+    let window = Duration::from_secs(wt_cfg.session_active_minutes * 60);
+    let sessions = session::active_sessions(window);
+    // Absolute path of each entry in `info.worktrees`, for session matching
+    let mut abs_paths: Vec<PathBuf> = Vec::new();
 
-    let worktree_names = match repo.worktrees() {
-        Ok(wt) => wt,
-        Err(_) => return info,
-    };
+    // From a linked worktree, the main one is the repository owning the common dir
+    if repo.is_worktree() {
+        if let Ok(main_repo) = Repository::open(repo.commondir()) {
+            if let Some(workdir) = main_repo.workdir() {
+                let path_str = workdir.to_string_lossy().trim_end_matches('/').to_string();
+                let name = workdir
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| path_str.clone());
+                let mut wt = unopened(name, &path_str);
+                wt.is_main = true;
+                info.worktrees.push(wt);
+                abs_paths.push(workdir.to_path_buf());
+            }
+        }
+    }
 
-    for name_opt in worktree_names.iter() {
-        if let Some(name) = name_opt {
+    if let Ok(worktree_names) = repo.worktrees() {
+        for name in worktree_names.iter().flatten() {
             // Get the worktree object to access its path
             let worktree = match repo.find_worktree(name) {
                 Ok(wt) => wt,
                 Err(_) => continue,
             };
 
-            // Get the worktree path
+            // All worktrees from repo.worktrees() are linked worktrees, not the main one
             let wt_path = worktree.path();
-            let path_str = wt_path.to_string_lossy().to_string();
-            let shortened_path = shorten_path(&path_str);
-
-            // Get the branch name for this worktree
-            let branch = get_worktree_branch(&repo, name);
+            let mut wt = unopened(name.to_string(), &wt_path.to_string_lossy());
+            wt.locked = match worktree.is_locked() {
+                Ok(WorktreeLockStatus::Locked(reason)) => Some(reason.unwrap_or_default()),
+                _ => None,
+            };
+            wt.prunable = worktree.is_prunable(None).unwrap_or(false);
+            info.worktrees.push(wt);
+            abs_paths.push(wt_path.to_path_buf());
+        }
+    }
 
-            // All worktrees from repo.worktrees() are linked worktrees, not the main one
-            info.worktrees.push(Worktree {
-                name: name.to_string(),
-                branch,
-                path: shortened_path,
-                is_main: false,
+    // Describe each worktree in parallel so their status budgets overlap;
+    // collapsed lists still need status for the dirty count. A directory
+    // removed without `git worktree remove` can't be opened and keeps its
+    // defaults.
+    std::thread::scope(|scope| {
+        for (wt, path) in info.worktrees.iter_mut().zip(&abs_paths) {
+            scope.spawn(move || {
+                if let Ok(wt_repo) = Repository::open(path) {
+                    describe(wt, &wt_repo, cfg, status_ttl);
+                }
             });
         }
+    });
+
+    for wt in &mut info.worktrees {
+        wt.is_current = current.as_deref().is_some_and(|c| same_path(c, &wt.path));
     }
+    attach_sessions(&mut info.worktrees, &abs_paths, &sessions);
 
     info
 }
 
-/// Get the current branch name for a specific worktree.
-fn get_worktree_branch(repo: &Repository, name: &str) -> Option<String> {
-    let wt = repo.find_worktree(name).ok()?;
-    // Try to open the worktree as a repo to get its HEAD
-    let wt_path = wt.path();
-    let wt_repo = Repository::open(wt_path).ok()?;
-    let head = wt_repo.head().ok()?;
-    head.shorthand().map(|s| s.to_string())
+/// A worktree entry before its repository has been opened.
+fn unopened(name: String, path: &str) -> Worktree {
+    Worktree {
+        name,
+        branch: None,
+        path: shorten_path(path),
+        is_main: false,
+        is_current: false,
+        is_clean: None,
+        ahead_behind: None,
+        last_commit: None,
+        locked: None,
+        prunable: false,
+        sessions: 0,
+        last_session_activity: None,
+    }
+}
+
+/// Fill in branch, ahead/behind, last commit and the cached or
+/// time-budgeted status of an opened worktree.
+fn describe(wt: &mut Worktree, repo: &Repository, cfg: &GitStatusConfig, status_ttl: u64) {
+    let head = repo.head().ok();
+    wt.branch = head.as_ref().and_then(|h| h.shorthand().map(|s| s.to_string()));
+    wt.last_commit = head
+        .as_ref()
+        .and_then(|h| h.peel_to_commit().ok())
+        .and_then(|c| Local.timestamp_opt(c.time().seconds(), 0).single());
+    wt.ahead_behind = git::upstream_ahead_behind(repo);
+    wt.is_clean = git::is_clean(repo, cfg, status_ttl);
+}
+
+/// Assign each session to the worktree containing its cwd. Worktrees can be
+/// nested inside the main one, so the deepest matching path wins.
+fn attach_sessions(worktrees: &mut [Worktree], abs_paths: &[PathBuf], sessions: &[ActiveSession]) {
+    for s in sessions {
+        let best = abs_paths
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| s.is_in(p))
+            .max_by_key(|(_, p)| p.as_os_str().len());
+        if let Some((i, _)) = best {
+            let wt = &mut worktrees[i];
+            let at = s.last_activity.with_timezone(&Local);
+            wt.sessions += 1;
+            wt.last_session_activity = Some(wt.last_session_activity.map_or(at, |l| l.max(at)));
+        }
+    }
+}
+
+/// Compare a workdir against a (possibly `~`-shortened) worktree path.
+fn same_path(workdir: &Path, shortened: &str) -> bool {
+    let workdir = workdir.to_string_lossy();
+    shorten_path(workdir.trim_end_matches('/')) == shortened.trim_end_matches('/')
 }
 
 /// Shorten file paths by replacing the home directory with ~.